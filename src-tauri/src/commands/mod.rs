@@ -1,19 +1,55 @@
 use crate::data;
-use crate::models::{InstalledSkill, Repository, Skill, SyncResult};
-use crate::services::{CacheService, ConfigService, GitHubService, SkillService};
-use std::sync::Mutex;
-use tauri::State;
+use crate::models::{
+    CatalogChangedEvent, ConflictResolution, FileMergeStatus, GitHubAuthStatus, InstalledSkill,
+    Lockfile, OfflineReason, OfflineStatus, OperationKind, PlanAction, PlanItem, PlanItemResult,
+    ProfileActivation, ProgressDetail, ProgressStage, RateLimitInfo, Repository,
+    RepositorySyncReport, RepositorySyncStatus, Skill, SkillOrigin, SkillProfile, SkillUpdateInfo,
+    SkillUpdateResult, SkillVersion, SkillsApplyResult, SkillsPlan, SyncErrorKind, SyncResult,
+    TokenSource, TokenValidation, TrashEntry, VersionReason,
+};
+use crate::services::archive::{ArchiveCache, ArchiveError};
+use crate::services::cache::RepoCache;
+use crate::services::catalog::SkillCatalog;
+use crate::services::config::AppConfig;
+use crate::services::github::{directory_blobs, split_repository, tree_entry_sha, GitHubError};
+use crate::services::merge::{self, MergeOutcome, SkillFiles};
+use crate::services::operations::{Operation, OperationRegistry, CANCELLED};
+use crate::services::{
+    CacheService, ConfigService, CredentialService, GitHubService, LockService, SkillService,
+    TeamService, TrashService, VersionService,
+};
+use futures::future::join_all;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 pub struct AppState {
     pub github: GitHubService,
-    pub skills_cache: Mutex<Vec<Skill>>,
+    pub catalog: Mutex<SkillCatalog>,
+    pub operations: OperationRegistry,
 }
 
 impl AppState {
     pub fn new() -> Self {
+        let github = GitHubService::new();
+        if let Ok(config) = ConfigService::load() {
+            github.set_retry_policy(config.retry_policy);
+            github.set_max_concurrent_requests(config.max_concurrent_requests);
+            if config.offline {
+                github.set_offline(Some(OfflineReason::User));
+            }
+        }
+        match CredentialService::load() {
+            Ok(credentials) => github.set_credentials(credentials),
+            Err(e) => eprintln!("[Rust] Failed to load GitHub tokens: {}", e),
+        }
+
         Self {
-            github: GitHubService::new(),
-            skills_cache: Mutex::new(Vec::new()),
+            github,
+            catalog: Mutex::new(load_catalog()),
+            operations: OperationRegistry::default(),
         }
     }
 }
@@ -24,6 +60,36 @@ impl Default for AppState {
     }
 }
 
+/// Restore the store catalog from the builtin skills and the cache of each
+/// repository, so the store can be shown before the first sync
+fn load_catalog() -> SkillCatalog {
+    let repositories = match ConfigService::list_repositories() {
+        Ok(repositories) => repositories,
+        Err(e) => {
+            eprintln!("[Rust] Failed to load repositories: {}", e);
+            return SkillCatalog::default();
+        }
+    };
+
+    let skills: Vec<Skill> = repositories
+        .iter()
+        .filter_map(|repo| {
+            if is_builtin_repository(repo) {
+                Some(data::load_builtin_skills())
+            } else {
+                CacheService::load_repo_cache(&repo.id)
+                    .ok()
+                    .flatten()
+                    .map(|cache| cache.skills)
+            }
+        })
+        .flatten()
+        .collect();
+
+    println!("[Rust] Restored {} skills from disk", skills.len());
+    SkillCatalog::new(skills)
+}
+
 /// Fetch skills from a GitHub repository
 #[tauri::command]
 pub async fn fetch_store_skills(
@@ -31,6 +97,7 @@ pub async fn fetch_store_skills(
     owner: String,
     repo: String,
 ) -> Result<Vec<Skill>, String> {
+    retry_connection(&state.github);
     let skills = state
         .github
         .scan_skills(&owner, &repo, None, None)
@@ -38,133 +105,462 @@ pub async fn fetch_store_skills(
         .map_err(|e| e.to_string())?;
 
     // Update cache
-    let mut cache = state.skills_cache.lock().map_err(|e| e.to_string())?;
-    *cache = skills.clone();
+    let mut catalog = state.catalog.lock().map_err(|e| e.to_string())?;
+    *catalog = SkillCatalog::new(skills.clone());
 
     Ok(skills)
 }
 
-/// Sync skills from all configured repositories
-#[tauri::command]
-pub async fn sync_repositories(state: State<'_, AppState>) -> Result<SyncResult, String> {
-    println!("[Rust] sync_repositories called");
-
-    // Load configured repositories
-    let repositories = ConfigService::list_repositories().map_err(|e| e.to_string())?;
-    println!("[Rust] Found {} repositories", repositories.len());
-
-    let mut all_skills = Vec::new();
-    let mut total_count = 0u32;
-    let mut errors = Vec::new();
-
-    // Default builtin repository URL
-    const BUILTIN_REPO: &str = "ComposioHQ/awesome-claude-skills";
-
-    for repo in &repositories {
-        // Check if this is the builtin repository
-        if repo.url == BUILTIN_REPO && repo.is_builtin {
-            // Load from builtin data - no network request needed
-            let builtin_skills = data::load_builtin_skills();
-            let count = builtin_skills.len() as u32;
-            total_count += count;
-            all_skills.extend(builtin_skills);
-            println!(
-                "[Rust] Loaded {} builtin skills from {}",
-                count, BUILTIN_REPO
-            );
-
-            // Update sync info
-            let _ = ConfigService::update_repository_sync(&repo.id, count);
-            continue;
+/// How often a sync waits out a rate limit on one repository before giving up on it
+const MAX_RATE_LIMIT_PAUSES: u32 = 3;
+
+/// Scan a repository during a sync. When GitHub rate limits the scan, the
+/// sync pauses until the limit resets and resumes with the same repository.
+async fn scan_repository(
+    github: &GitHubService,
+    owner: &str,
+    repo: &str,
+    base_path: Option<&str>,
+    git_ref: Option<&str>,
+    operation: Option<&Operation>,
+) -> Result<Vec<Skill>, GitHubError> {
+    let mut pauses = 0;
+    loop {
+        match github.scan_skills(owner, repo, base_path, git_ref).await {
+            Err(e) if pauses < MAX_RATE_LIMIT_PAUSES => {
+                let Some(wait) = e.retry_after() else {
+                    return Err(e);
+                };
+                println!(
+                    "[Rust] {} while scanning {}/{}, pausing sync for {}s",
+                    e,
+                    owner,
+                    repo,
+                    wait.as_secs()
+                );
+
+                let Some(operation) = operation else {
+                    tokio::time::sleep(wait).await;
+                    pauses += 1;
+                    continue;
+                };
+                let resume_at =
+                    chrono::Utc::now() + chrono::Duration::from_std(wait).unwrap_or_default();
+                operation.emit(
+                    ProgressStage::Progress,
+                    ProgressDetail {
+                        repository: Some(format!("{}/{}", owner, repo)),
+                        message: Some(format!(
+                            "Rate limited, waiting for reset at {}",
+                            resume_at.format("%H:%M:%S UTC")
+                        )),
+                        resume_at: Some(resume_at.to_rfc3339()),
+                        ..Default::default()
+                    },
+                );
+                // A cancelled sync gives up with the rate limit error
+                if operation.run(tokio::time::sleep(wait)).await.is_none() {
+                    return Err(e);
+                }
+                pauses += 1;
+            }
+            result => return result,
         }
+    }
+}
 
-        // For custom repositories, check local cache first
-        if let Ok(Some(cached_skills)) = CacheService::load_repo_cache(&repo.id) {
-            let count = cached_skills.len() as u32;
-            total_count += count;
-            all_skills.extend(cached_skills);
-            println!("[Rust] Loaded {} skills from cache for {}", count, repo.url);
+/// Builtin repository, whose skills ship with the app
+const BUILTIN_REPO: &str = "ComposioHQ/awesome-claude-skills";
 
-            // Update sync info
-            let _ = ConfigService::update_repository_sync(&repo.id, count);
-            continue;
-        }
+fn is_builtin_repository(repo: &Repository) -> bool {
+    repo.url == BUILTIN_REPO && repo.is_builtin
+}
 
-        // Parse owner/repo from url
-        let parts: Vec<&str> = repo.url.split('/').collect();
-        if parts.len() != 2 {
-            errors.push(format!("Invalid repository URL: {}", repo.url));
-            continue;
+/// Classify a scan error for the sync report
+fn sync_error_kind(error: &GitHubError) -> SyncErrorKind {
+    match error {
+        GitHubError::NotFound(_) => SyncErrorKind::NotFound,
+        GitHubError::RateLimited { .. } | GitHubError::SecondaryRateLimited { .. } => {
+            SyncErrorKind::RateLimited
         }
+        GitHubError::Forbidden(_) => SyncErrorKind::Forbidden,
+        GitHubError::Offline => SyncErrorKind::Offline,
+        GitHubError::Network(_) | GitHubError::RetriesExhausted { .. } => SyncErrorKind::Network,
+        _ => SyncErrorKind::Other,
+    }
+}
 
-        let owner = parts[0];
-        let repo_name = parts[1];
+/// Load the skills of one repository: from the bundled data for the builtin
+/// repository, from the local cache when `use_cache` is set and a cache
+/// exists, or by scanning it on GitHub
+async fn load_repository_skills(
+    github: &GitHubService,
+    repo: &Repository,
+    use_cache: bool,
+    operation: Option<&Operation>,
+) -> (RepositorySyncReport, Vec<Skill>) {
+    let started = Instant::now();
+    let mut report = RepositorySyncReport {
+        repository_id: repo.id.clone(),
+        repository: repo.url.clone(),
+        status: RepositorySyncStatus::Failed,
+        skill_count: 0,
+        duration_ms: 0,
+        error_kind: None,
+        error: None,
+        cache_age_secs: None,
+        commit_sha: None,
+    };
+
+    let skills = if is_builtin_repository(repo) {
+        // Load from builtin data - no network request needed
+        let skills = data::load_builtin_skills();
+        println!(
+            "[Rust] Loaded {} builtin skills from {}",
+            skills.len(),
+            BUILTIN_REPO
+        );
+        report.status = RepositorySyncStatus::Builtin;
+        skills
+    } else if let Some(cache) = use_cache
+        .then(|| CacheService::load_repo_cache(&repo.id).ok().flatten())
+        .flatten()
+    {
+        println!(
+            "[Rust] Loaded {} skills from cache for {}",
+            cache.skills.len(),
+            repo.url
+        );
+        report.status = RepositorySyncStatus::Cached;
+        report.cache_age_secs = Some(cache.age().as_secs());
+        report.commit_sha = cache.commit_sha;
+        cache.skills
+    } else if let Some((owner, repo_name)) = split_repository(&repo.url) {
         println!("[Rust] Scanning repository: {}/{}", owner, repo_name);
 
-        match state
-            .github
-            .scan_skills(
-                owner,
-                repo_name,
-                repo.base_path.as_deref(),
-                repo.git_ref.as_deref(),
-            )
+        // Resolved before the scan, so a push during it is picked up by the next check
+        report.commit_sha = github
+            .resolve_commit_sha(owner, repo_name, repo.git_ref.as_deref())
             .await
+            .ok();
+
+        match scan_repository(
+            github,
+            owner,
+            repo_name,
+            repo.base_path.as_deref(),
+            repo.git_ref.as_deref(),
+            operation,
+        )
+        .await
         {
             Ok(skills) => {
-                let count = skills.len() as u32;
-                total_count += count;
-
-                // Cache the skills for this repository
-                let _ = CacheService::save_repo_cache(&repo.id, &skills);
-
-                all_skills.extend(skills);
-                println!("[Rust] Found {} skills in {}/{}", count, owner, repo_name);
-
-                // Update sync info
-                let _ = ConfigService::update_repository_sync(&repo.id, count);
+                println!(
+                    "[Rust] Found {} skills in {}/{}",
+                    skills.len(),
+                    owner,
+                    repo_name
+                );
+                report.status = RepositorySyncStatus::Scanned;
+                skills
             }
             Err(e) => {
-                let error_msg = format!("{}: {}", repo.url, e);
                 println!("[Rust] Error scanning {}/{}: {}", owner, repo_name, e);
-                errors.push(error_msg);
+                report.error_kind = Some(sync_error_kind(&e));
+                report.error = Some(e.to_string());
+                Vec::new()
             }
         }
+    } else {
+        report.error_kind = Some(SyncErrorKind::InvalidRepository);
+        report.error = Some(format!("Invalid repository URL: {}", repo.url));
+        Vec::new()
+    };
+
+    report.skill_count = skills.len() as u32;
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    (report, skills)
+}
+
+/// Load one repository of a sync, reporting when it starts and ends
+async fn load_repository_with_progress(
+    state: &AppState,
+    operation: &Operation,
+    repo: &Repository,
+    use_cache: bool,
+    done: &AtomicU32,
+    total: u32,
+) -> (RepositorySyncReport, Vec<Skill>) {
+    operation.emit(
+        ProgressStage::Started,
+        ProgressDetail {
+            repository: Some(repo.url.clone()),
+            ..Default::default()
+        },
+    );
+
+    let (report, skills) =
+        load_repository_skills(&state.github, repo, use_cache, Some(operation)).await;
+
+    let detail = ProgressDetail {
+        repository: Some(repo.url.clone()),
+        done: Some(done.fetch_add(1, Ordering::SeqCst) + 1),
+        total: Some(total),
+        ..Default::default()
+    };
+    match &report.error {
+        None => operation.emit(
+            ProgressStage::Finished,
+            ProgressDetail {
+                message: Some(format!("{} skills", report.skill_count)),
+                ..detail
+            },
+        ),
+        Some(e) => operation.emit(
+            ProgressStage::Failed,
+            ProgressDetail {
+                message: Some(e.clone()),
+                ..detail
+            },
+        ),
+    }
+
+    (report, skills)
+}
+
+/// Cache freshly scanned skills and record when the repository was synced
+fn save_repository_sync(report: &RepositorySyncReport, skills: &[Skill]) {
+    if report.status == RepositorySyncStatus::Scanned {
+        let _ = CacheService::save_repo_cache(
+            &report.repository_id,
+            skills,
+            report.commit_sha.as_deref(),
+        );
+    }
+    if report.status != RepositorySyncStatus::Failed {
+        let _ = ConfigService::update_repository_sync(&report.repository_id, report.skill_count);
+    }
+}
+
+/// Load every repository concurrently and replace the in-memory catalog.
+/// Results are combined in configuration order, so the catalog does not
+/// depend on which repository finishes first. GitHubService bounds the
+/// number of requests in flight.
+///
+/// Nothing is written until every repository is done, so a cancelled sync
+/// leaves the caches and the catalog as they were. A forced sync (without
+/// `use_cache`) replaces all repository caches.
+async fn sync_all(
+    state: &AppState,
+    operation: &Operation,
+    repositories: &[Repository],
+    use_cache: bool,
+) -> Result<(u32, Vec<RepositorySyncReport>), String> {
+    // Offline, the caches are served and kept even when a refresh was asked for
+    let use_cache = use_cache || state.github.is_offline();
+    let total = repositories.len() as u32;
+    let done = AtomicU32::new(0);
+    operation.emit(
+        ProgressStage::Started,
+        ProgressDetail {
+            total: Some(total),
+            ..Default::default()
+        },
+    );
+
+    let results = operation
+        .run(join_all(repositories.iter().map(|repo| {
+            load_repository_with_progress(state, operation, repo, use_cache, &done, total)
+        })))
+        .await;
+
+    let Some(results) = results else {
+        println!("[Rust] Sync {} cancelled", operation.id());
+        operation.emit(ProgressStage::Cancelled, ProgressDetail::default());
+        return Err(CANCELLED.to_string());
+    };
+
+    if !use_cache {
+        let _ = CacheService::clear_all_cache();
+        println!("[Rust] Cache cleared");
     }
 
+    let mut all_skills = Vec::new();
+    let mut reports = Vec::new();
+    for (report, skills) in results {
+        save_repository_sync(&report, &skills);
+        all_skills.extend(skills);
+        reports.push(report);
+    }
+
+    let total_count = all_skills.len() as u32;
+
     // Update in-memory cache
-    let mut cache = state.skills_cache.lock().map_err(|e| e.to_string())?;
-    *cache = all_skills;
+    let mut catalog = state.catalog.lock().map_err(|e| e.to_string())?;
+    *catalog = SkillCatalog::new(all_skills);
+
+    operation.emit(
+        ProgressStage::Finished,
+        ProgressDetail {
+            done: Some(total),
+            total: Some(total),
+            message: Some(format!("{} skills", total_count)),
+            ..Default::default()
+        },
+    );
+
+    Ok((total_count, reports))
+}
+
+/// Summarize a sync for the frontend
+fn sync_result(total_count: u32, reports: Vec<RepositorySyncReport>, forced: bool) -> SyncResult {
+    let errors: Vec<String> = reports
+        .iter()
+        .filter_map(|r| Some(format!("{}: {}", r.repository, r.error.as_deref()?)))
+        .collect();
 
-    let message = if errors.is_empty() {
-        format!(
+    let message = match (errors.is_empty(), forced) {
+        (true, false) => format!(
             "Successfully synced {} skills from {} repositories",
             total_count,
-            repositories.len()
-        )
-    } else {
-        format!(
+            reports.len()
+        ),
+        (true, true) => format!(
+            "Force synced {} skills from {} repositories",
+            total_count,
+            reports.len()
+        ),
+        (false, false) => format!(
             "Synced {} skills with {} errors: {}",
             total_count,
             errors.len(),
             errors.join("; ")
-        )
+        ),
+        (false, true) => format!(
+            "Force synced {} skills with {} errors: {}",
+            total_count,
+            errors.len(),
+            errors.join("; ")
+        ),
     };
 
-    println!("[Rust] sync_repositories complete: {}", message);
-
-    Ok(SyncResult {
+    SyncResult {
         success: errors.is_empty(),
         skills_found: total_count,
         message,
-    })
+        repositories: reports,
+    }
+}
+
+/// Event that `ProgressEvent`s of syncs and installs are emitted as
+const PROGRESS_EVENT: &str = "operation-progress";
+
+/// Register an operation whose progress is emitted to the frontend
+fn start_operation(
+    app: &AppHandle,
+    state: &AppState,
+    operation_id: Option<String>,
+    kind: OperationKind,
+) -> Arc<Operation> {
+    let app = app.clone();
+    state.operations.start(
+        operation_id,
+        kind,
+        Arc::new(move |event| {
+            let _ = app.emit(PROGRESS_EVENT, event);
+        }),
+    )
+}
+
+/// Leave the offline mode entered after connection failures, so a sync,
+/// install or check the user asks for tries GitHub again. It is entered again if GitHub is still
+/// out of reach.
+fn retry_connection(github: &GitHubService) {
+    if github.offline_reason() == Some(OfflineReason::ConnectionFailures) {
+        println!("[Rust] Trying to reach GitHub again");
+        github.set_offline(None);
+    }
+}
+
+/// Register a sync of `repositories`, which background refreshes leave alone
+fn start_sync_operation(
+    app: &AppHandle,
+    state: &AppState,
+    operation_id: Option<String>,
+    repositories: &[Repository],
+) -> Arc<Operation> {
+    let operation = start_operation(app, state, operation_id, OperationKind::Sync);
+    state.operations.set_repositories(
+        operation.id(),
+        repositories.iter().map(|repo| repo.id.clone()).collect(),
+    );
+    operation
+}
+
+/// Cancel a running sync or install. Partial results are discarded.
+#[tauri::command]
+pub fn cancel_operation(state: State<'_, AppState>, operation_id: String) -> bool {
+    println!("[Rust] cancel_operation called with: {}", operation_id);
+    state.operations.cancel(&operation_id)
+}
+
+/// Sync skills from all configured repositories, reporting progress under
+/// `operation_id`
+#[tauri::command]
+pub async fn sync_repositories(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    operation_id: Option<String>,
+) -> Result<SyncResult, String> {
+    println!("[Rust] sync_repositories called");
+    retry_connection(&state.github);
+
+    // Load configured repositories
+    let repositories = ConfigService::list_repositories().map_err(|e| e.to_string())?;
+    println!("[Rust] Found {} repositories", repositories.len());
+
+    let operation = start_sync_operation(&app, &state, operation_id, &repositories);
+    let result = sync_all(&state, &operation, &repositories, true).await;
+    state.operations.finish(operation.id());
+    let (total_count, reports) = result?;
+
+    let result = sync_result(total_count, reports, false);
+    println!("[Rust] sync_repositories complete: {}", result.message);
+    Ok(result)
 }
 
 /// Get cached skills
 #[tauri::command]
 pub fn get_cached_skills(state: State<'_, AppState>) -> Result<Vec<Skill>, String> {
-    let cache = state.skills_cache.lock().map_err(|e| e.to_string())?;
-    Ok(cache.clone())
+    let catalog = state.catalog.lock().map_err(|e| e.to_string())?;
+    Ok(catalog.skills().to_vec())
+}
+
+/// Get a skill of the store catalog by id
+#[tauri::command]
+pub fn get_skill(state: State<'_, AppState>, skill_id: String) -> Result<Option<Skill>, String> {
+    let catalog = state.catalog.lock().map_err(|e| e.to_string())?;
+    Ok(catalog.get(&skill_id).cloned())
+}
+
+/// Get the skills of one configured repository from the store catalog
+#[tauri::command]
+pub fn get_repository_skills(
+    state: State<'_, AppState>,
+    repo_id: String,
+) -> Result<Vec<Skill>, String> {
+    let repositories = ConfigService::list_repositories().map_err(|e| e.to_string())?;
+    let repo = repositories
+        .iter()
+        .find(|r| r.id == repo_id)
+        .ok_or_else(|| format!("Repository not found: {}", repo_id))?;
+
+    let catalog = state.catalog.lock().map_err(|e| e.to_string())?;
+    Ok(catalog
+        .repository_skills(&repo.url)
+        .into_iter()
+        .cloned()
+        .collect())
 }
 
 /// List installed skills
@@ -179,11 +575,54 @@ pub fn is_skill_installed(skill_name: String) -> Result<bool, String> {
     SkillService::is_installed(&skill_name).map_err(|e| e.to_string())
 }
 
-/// Install a skill
+/// Install a skill, reporting progress under `operation_id`
 #[tauri::command]
-pub async fn install_skill(state: State<'_, AppState>, skill_id: String) -> Result<String, String> {
+pub async fn install_skill(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    skill_id: String,
+    operation_id: Option<String>,
+) -> Result<String, String> {
     println!("[Rust] install_skill called with: {}", skill_id);
+    retry_connection(&state.github);
+
+    let operation = start_operation(&app, &state, operation_id, OperationKind::Install);
+    operation.emit(
+        ProgressStage::Started,
+        ProgressDetail {
+            skill: Some(skill_id.clone()),
+            ..Default::default()
+        },
+    );
 
+    let result = install_from_github(&state.github, &operation, &skill_id).await;
+    state.operations.finish(operation.id());
+
+    let (stage, message) = match &result {
+        Ok(_) => (ProgressStage::Finished, None),
+        Err(_) if operation.is_cancelled() => (ProgressStage::Cancelled, None),
+        Err(e) => (ProgressStage::Failed, Some(e.clone())),
+    };
+    operation.emit(
+        stage,
+        ProgressDetail {
+            skill: Some(skill_id),
+            message,
+            ..Default::default()
+        },
+    );
+
+    result
+}
+
+/// Download a skill from GitHub and install it. Cancelling the operation
+/// stops the download; the partial download is deleted and nothing is
+/// installed.
+async fn install_from_github(
+    github: &GitHubService,
+    operation: &Arc<Operation>,
+    skill_id: &str,
+) -> Result<String, String> {
     // Parse skill_id:
     // - "owner/repo" (SKILL.md at repo root)
     // - "owner/repo/path/to/skill"
@@ -227,40 +666,560 @@ pub async fn install_skill(state: State<'_, AppState>, skill_id: String) -> Resu
         .and_then(|repos| repos.into_iter().find(|r| r.url == repo_url))
         .and_then(|r| r.git_ref);
 
-    // Download entire skill directory (SKILL.md + resources/scripts/etc.)
-    println!(
-        "[Rust] install_skill downloading directory: {}/{}/{}",
-        owner, repo, skill_path
-    );
-    let files = state
-        .github
-        .download_directory_files(owner, repo, &skill_path, git_ref.as_deref())
+    let (files, origin) = operation
+        .run(download_skill(
+            github,
+            &mut ArchiveCache::with_operation(operation.clone()),
+            owner,
+            repo,
+            &skill_path,
+            git_ref,
+            None,
+        ))
         .await
+        .ok_or_else(|| CANCELLED.to_string())?
         .map_err(|e| {
             println!("[Rust] install_skill download error: {}", e);
-            e.to_string()
+            e
         })?;
 
-    println!("[Rust] install_skill files fetched: {}", files.len());
+    if operation.is_cancelled() {
+        return Err(CANCELLED.to_string());
+    }
+
+    operation.emit(
+        ProgressStage::Progress,
+        ProgressDetail {
+            skill: Some(skill_name.to_string()),
+            done: Some(files.len() as u32),
+            total: Some(files.len() as u32),
+            ..Default::default()
+        },
+    );
 
-    let result = SkillService::install_skill(skill_name, files).map_err(|e| {
+    let result = SkillService::install_skill(skill_name, files, Some(origin)).map_err(|e| {
         println!("[Rust] install_skill install error: {}", e);
         e.to_string()
     })?;
 
+    let _ = snapshot_skill(skill_name, VersionReason::Install);
+
     println!("[Rust] install_skill success: {}", result);
     Ok(result)
 }
 
+/// Keep the blob cache of downloaded skill files within its size limit
+fn prune_blob_cache() {
+    let max_bytes = ConfigService::load()
+        .map(|c| c.blob_cache_max_bytes())
+        .unwrap_or_else(|_| AppConfig::default().blob_cache_max_bytes());
+
+    if let Err(e) = CacheService::prune_blobs(max_bytes) {
+        eprintln!("[Rust] Failed to prune the blob cache: {}", e);
+    }
+}
+
+/// Download a skill directory at `commit_sha`, or pinned to the commit
+/// `git_ref` currently points at
+///
+/// The files are read from the blob cache when the recursive tree at the
+/// commit lists all of them there, and otherwise taken from the repository
+/// tarball, shared through `archives` with other skills of the same batch.
+/// Downloaded files are added to the blob cache, which is then pruned.
+async fn download_skill(
+    github: &GitHubService,
+    archives: &mut ArchiveCache,
+    owner: &str,
+    repo: &str,
+    skill_path: &str,
+    git_ref: Option<String>,
+    commit_sha: Option<String>,
+) -> Result<(Vec<(String, Vec<u8>)>, SkillOrigin), String> {
+    // Pin the ref to a commit so the files and the recorded provenance match
+    let commit_sha = match commit_sha {
+        Some(commit_sha) => commit_sha,
+        None => github
+            .resolve_commit_sha(owner, repo, git_ref.as_deref())
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    // The tree at a commit never changes, so it is usually served by the HTTP
+    // cache. A truncated tree may not list every file of the skill.
+    let tree = github
+        .fetch_tree(owner, repo, Some(&commit_sha))
+        .await
+        .ok()
+        .filter(|tree| !tree.truncated);
+    let tree_sha = match tree
+        .as_ref()
+        .and_then(|tree| tree_entry_sha(tree, skill_path))
+    {
+        Some(tree_sha) => tree_sha,
+        None => github
+            .fetch_tree_sha(owner, repo, skill_path, Some(&commit_sha))
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+
+    // Download entire skill directory (SKILL.md + resources/scripts/etc.)
+    println!(
+        "[Rust] downloading directory: {}/{}/{}@{}",
+        owner, repo, skill_path, commit_sha
+    );
+    // Skills downloaded before, by this or another repository, need no download
+    let cached = tree
+        .as_ref()
+        .map(|tree| directory_blobs(tree, skill_path))
+        .filter(|blobs| !blobs.is_empty())
+        .and_then(|blobs| CacheService::load_blobs(&blobs));
+    let downloaded = cached.is_none();
+    let files = match cached {
+        Some(files) => {
+            println!("[Rust] files read from the blob cache");
+            files
+        }
+        None => match archives
+            .skill_files(github, owner, repo, &commit_sha, skill_path)
+            .await
+        {
+            Ok(files) => {
+                for (path, bytes) in &files {
+                    if let Err(e) = CacheService::save_blob(bytes) {
+                        eprintln!("[Rust] Failed to cache {}: {}", path, e);
+                    }
+                }
+                files
+            }
+            Err(ArchiveError::Cancelled) => return Err(CANCELLED.to_string()),
+            Err(e) => {
+                println!(
+                    "[Rust] tarball download failed ({}), fetching files one by one",
+                    e
+                );
+                github
+                    .download_directory_files(
+                        owner,
+                        repo,
+                        skill_path,
+                        Some(&commit_sha),
+                        archives.operation(),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+        },
+    };
+
+    println!("[Rust] files fetched: {}", files.len());
+    if downloaded {
+        prune_blob_cache();
+    }
+
+    let origin = SkillOrigin {
+        repository: format!("{}/{}", owner, repo),
+        path: skill_path.to_string(),
+        git_ref,
+        commit_sha: Some(commit_sha),
+        tree_sha: Some(tree_sha),
+    };
+
+    Ok((files, origin))
+}
+
+/// Check installed skills against their source repositories for updates
+#[tauri::command]
+pub async fn check_skill_updates(
+    state: State<'_, AppState>,
+) -> Result<Vec<SkillUpdateInfo>, String> {
+    println!("[Rust] check_skill_updates called");
+    retry_connection(&state.github);
+
+    let installed = SkillService::list_installed().map_err(|e| e.to_string())?;
+    let mut updates = Vec::new();
+
+    for skill in installed {
+        // Skills without provenance (custom or installed by hand) cannot be checked
+        let Some(origin) = skill.origin else {
+            continue;
+        };
+
+        let mut info = SkillUpdateInfo {
+            skill_name: skill.id,
+            repository: origin.repository.clone(),
+            path: origin.path.clone(),
+            installed_sha: None,
+            latest_sha: None,
+            has_update: false,
+            error: None,
+        };
+
+        let Some((owner, repo)) = split_repository(&origin.repository) else {
+            info.error = Some(format!("Invalid repository: {}", origin.repository));
+            updates.push(info);
+            continue;
+        };
+
+        // Compare the skill directory's tree SHA when known, otherwise the commit
+        let latest = match &origin.tree_sha {
+            Some(tree_sha) => {
+                info.installed_sha = Some(tree_sha.clone());
+                state
+                    .github
+                    .fetch_tree_sha(owner, repo, &origin.path, origin.git_ref.as_deref())
+                    .await
+            }
+            None => {
+                info.installed_sha = origin.commit_sha.clone();
+                state
+                    .github
+                    .resolve_commit_sha(owner, repo, origin.git_ref.as_deref())
+                    .await
+            }
+        };
+
+        match latest {
+            Ok(latest_sha) => {
+                info.has_update = info.installed_sha.as_deref() != Some(latest_sha.as_str());
+                info.latest_sha = Some(latest_sha);
+            }
+            Err(e) => {
+                println!(
+                    "[Rust] check_skill_updates error for '{}': {}",
+                    info.skill_name, e
+                );
+                info.error = Some(e.to_string());
+            }
+        }
+
+        updates.push(info);
+    }
+
+    println!(
+        "[Rust] check_skill_updates complete: {} of {} outdated",
+        updates.iter().filter(|u| u.has_update).count(),
+        updates.len()
+    );
+
+    Ok(updates)
+}
+
+/// Download a new version of an installed skill and three-way merge it with
+/// local edits, using the originally installed copy as the base.
+///
+/// `source` defaults to the head of the recorded source's ref.
+async fn merge_skill_update(
+    github: &GitHubService,
+    archives: &mut ArchiveCache,
+    skill_name: &str,
+    resolutions: &HashMap<String, ConflictResolution>,
+    source: Option<SkillOrigin>,
+    content_hash: Option<&str>,
+) -> Result<(MergeOutcome, Vec<(String, Vec<u8>)>, SkillOrigin), String> {
+    let manifest = SkillService::get_manifest(skill_name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Skill '{}' has no recorded source", skill_name))?;
+    let origin = manifest
+        .origin
+        .clone()
+        .ok_or_else(|| format!("Skill '{}' has no recorded source", skill_name))?;
+
+    let source = source.unwrap_or(SkillOrigin {
+        commit_sha: None,
+        ..origin
+    });
+    let (owner, repo) = split_repository(&source.repository)
+        .ok_or_else(|| format!("Invalid repository: {}", source.repository))?;
+
+    let (upstream, origin) = download_skill(
+        github,
+        archives,
+        owner,
+        repo,
+        &source.path,
+        source.git_ref,
+        source.commit_sha,
+    )
+    .await?;
+
+    if let Some(expected) = content_hash {
+        LockService::verify(skill_name, &upstream, expected).map_err(|e| e.to_string())?;
+    }
+
+    let mine = SkillService::read_installed_files(skill_name).map_err(|e| e.to_string())?;
+    let base =
+        SkillService::load_merge_base(skill_name, &manifest, &mine).map_err(|e| e.to_string())?;
+
+    let theirs: SkillFiles = upstream.iter().cloned().collect();
+    let outcome = merge::merge_skill_files(&base, &mine, &theirs, resolutions);
+
+    Ok((outcome, upstream, origin))
+}
+
+/// Preview updating an installed skill: report which files would be
+/// updated, kept, merged or conflict without changing anything
+#[tauri::command]
+pub async fn preview_skill_update(
+    state: State<'_, AppState>,
+    skill_name: String,
+) -> Result<SkillUpdateResult, String> {
+    println!("[Rust] preview_skill_update called with: {}", skill_name);
+    retry_connection(&state.github);
+
+    let (outcome, _, _) = merge_skill_update(
+        &state.github,
+        &mut ArchiveCache::new(),
+        &skill_name,
+        &HashMap::new(),
+        None,
+        None,
+    )
+    .await?;
+
+    Ok(SkillUpdateResult {
+        has_conflicts: outcome
+            .results
+            .iter()
+            .any(|f| f.status == FileMergeStatus::Conflict),
+        skill_name,
+        path: None,
+        files: outcome.results,
+    })
+}
+
+/// Update an installed skill to the latest version of its source
+///
+/// Local edits are preserved with a three-way merge. Conflicting files are
+/// resolved per path through `resolutions` and get conflict markers by default.
+#[tauri::command]
+pub async fn update_skill(
+    state: State<'_, AppState>,
+    skill_name: String,
+    resolutions: Option<HashMap<String, ConflictResolution>>,
+) -> Result<SkillUpdateResult, String> {
+    println!("[Rust] update_skill called with: {}", skill_name);
+    retry_connection(&state.github);
+
+    run_skill_update(
+        &state.github,
+        &mut ArchiveCache::new(),
+        skill_name,
+        &resolutions.unwrap_or_default(),
+        None,
+        None,
+    )
+    .await
+}
+
+/// Merge a new version into an installed skill and swap it into place,
+/// snapshotting the current state first
+async fn run_skill_update(
+    github: &GitHubService,
+    archives: &mut ArchiveCache,
+    skill_name: String,
+    resolutions: &HashMap<String, ConflictResolution>,
+    source: Option<SkillOrigin>,
+    content_hash: Option<&str>,
+) -> Result<SkillUpdateResult, String> {
+    let (outcome, upstream, origin) = merge_skill_update(
+        github,
+        archives,
+        &skill_name,
+        resolutions,
+        source,
+        content_hash,
+    )
+    .await
+    .map_err(|e| {
+        println!("[Rust] update_skill error: {}", e);
+        e
+    })?;
+
+    snapshot_skill(&skill_name, VersionReason::Update)?;
+
+    let files: Vec<(String, Vec<u8>)> = outcome.files.into_iter().collect();
+    let path =
+        SkillService::replace_skill(&skill_name, &files, &upstream, Some(origin)).map_err(|e| {
+            println!("[Rust] update_skill replace error: {}", e);
+            e.to_string()
+        })?;
+
+    let has_conflicts = outcome
+        .results
+        .iter()
+        .any(|f| f.status == FileMergeStatus::Conflict);
+
+    println!(
+        "[Rust] update_skill success: {} ({} files changed, conflicts: {})",
+        path,
+        outcome.results.len(),
+        has_conflicts
+    );
+
+    Ok(SkillUpdateResult {
+        skill_name,
+        path: Some(path),
+        files: outcome.results,
+        has_conflicts,
+    })
+}
+
 /// Uninstall a skill
 #[tauri::command]
 pub fn uninstall_skill(skill_name: String) -> Result<(), String> {
     println!("[Rust] uninstall_skill called with: '{}'", skill_name);
+    snapshot_skill(&skill_name, VersionReason::Uninstall)?;
     let result = SkillService::uninstall_skill(&skill_name);
     match &result {
-        Ok(_) => println!("[Rust] uninstall_skill success for: '{}'", skill_name),
+        Ok(entry) => println!(
+            "[Rust] uninstall_skill moved '{}' to trash: {}",
+            skill_name, entry.id
+        ),
         Err(e) => println!("[Rust] uninstall_skill error for '{}': {}", skill_name, e),
     }
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Disable a skill without uninstalling it
+#[tauri::command]
+pub fn disable_skill(skill_name: String) -> Result<String, String> {
+    println!("[Rust] disable_skill called with: '{}'", skill_name);
+    SkillService::disable_skill(&skill_name).map_err(|e| e.to_string())
+}
+
+/// Re-enable a disabled skill
+#[tauri::command]
+pub fn enable_skill(skill_name: String) -> Result<String, String> {
+    println!("[Rust] enable_skill called with: '{}'", skill_name);
+    SkillService::enable_skill(&skill_name).map_err(|e| e.to_string())
+}
+
+// ===== Profile Commands =====
+
+/// List all skill profiles
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<SkillProfile>, String> {
+    ConfigService::list_profiles().map_err(|e| e.to_string())
+}
+
+/// Create a skill profile
+#[tauri::command]
+pub fn create_profile(name: String, skills: Vec<String>) -> Result<SkillProfile, String> {
+    ConfigService::create_profile(&name, skills).map_err(|e| e.to_string())
+}
+
+/// Update a skill profile's name and/or skills
+#[tauri::command]
+pub fn update_profile(
+    profile_id: String,
+    name: Option<String>,
+    skills: Option<Vec<String>>,
+) -> Result<SkillProfile, String> {
+    ConfigService::update_profile(&profile_id, name.as_deref(), skills).map_err(|e| e.to_string())
+}
+
+/// Delete a skill profile (installed skills are not touched)
+#[tauri::command]
+pub fn delete_profile(profile_id: String) -> Result<bool, String> {
+    ConfigService::delete_profile(&profile_id).map_err(|e| e.to_string())
+}
+
+/// Switch to a profile: enable its skills and park every other skill
+#[tauri::command]
+pub fn activate_profile(profile_id: String) -> Result<ProfileActivation, String> {
+    println!("[Rust] activate_profile called with: {}", profile_id);
+
+    let profile = ConfigService::get_profile(&profile_id).map_err(|e| e.to_string())?;
+    let wanted: HashSet<String> = profile.skills.iter().cloned().collect();
+
+    let missing = profile
+        .skills
+        .iter()
+        .filter(|name| !SkillService::is_installed(name).unwrap_or(false))
+        .cloned()
+        .collect();
+
+    let (enabled, disabled) = SkillService::set_enabled_skills(&wanted).map_err(|e| {
+        println!("[Rust] activate_profile error: {}", e);
+        e.to_string()
+    })?;
+
+    ConfigService::set_active_profile(Some(&profile_id)).map_err(|e| e.to_string())?;
+
+    println!(
+        "[Rust] activate_profile success: {} enabled, {} disabled",
+        enabled.len(),
+        disabled.len()
+    );
+
+    Ok(ProfileActivation {
+        profile_id,
+        enabled,
+        disabled,
+        missing,
+    })
+}
+
+// ===== Trash Commands =====
+
+/// List uninstalled skills in the trash, purging expired entries first
+#[tauri::command]
+pub fn list_trash() -> Result<Vec<TrashEntry>, String> {
+    if let Ok(config) = ConfigService::load() {
+        let _ = TrashService::purge_expired(config.trash_retention_days);
+    }
+    TrashService::list().map_err(|e| e.to_string())
+}
+
+/// Restore a skill from the trash
+#[tauri::command]
+pub fn restore_from_trash(trash_id: String) -> Result<String, String> {
+    println!("[Rust] restore_from_trash called with: {}", trash_id);
+    TrashService::restore(&trash_id).map_err(|e| e.to_string())
+}
+
+/// Permanently delete everything in the trash
+#[tauri::command]
+pub fn empty_trash() -> Result<usize, String> {
+    TrashService::empty().map_err(|e| e.to_string())
+}
+
+/// Save the current state of a skill to its version history
+fn snapshot_skill(skill_name: &str, reason: VersionReason) -> Result<(), String> {
+    VersionService::snapshot(skill_name, reason, max_skill_versions())
+        .map(|_| ())
+        .map_err(|e| {
+            println!("[Rust] snapshot error for '{}': {}", skill_name, e);
+            format!("Failed to save version of '{}': {}", skill_name, e)
+        })
+}
+
+/// How many versions of each skill to keep
+fn max_skill_versions() -> usize {
+    ConfigService::load()
+        .map(|c| c.max_skill_versions)
+        .unwrap_or_else(|_| AppConfig::default().max_skill_versions)
+}
+
+/// List saved versions of a skill, newest first
+#[tauri::command]
+pub fn list_skill_versions(skill_name: String) -> Result<Vec<SkillVersion>, String> {
+    VersionService::list_versions(&skill_name).map_err(|e| e.to_string())
+}
+
+/// Restore a saved version of a skill
+#[tauri::command]
+pub fn rollback_skill(skill_name: String, version_id: String) -> Result<String, String> {
+    println!(
+        "[Rust] rollback_skill called with: {}@{}",
+        skill_name, version_id
+    );
+
+    // The state being replaced is kept so the rollback itself can be undone
+    let result = VersionService::rollback(&skill_name, &version_id, max_skill_versions());
+    match &result {
+        Ok(path) => println!("[Rust] rollback_skill success: {}", path),
+        Err(e) => println!("[Rust] rollback_skill error: {}", e),
+    }
     result.map_err(|e| e.to_string())
 }
 
@@ -287,6 +1246,362 @@ pub fn get_skill_content(skill_name: String) -> Result<String, String> {
     result.map_err(|e| e.to_string())
 }
 
+// ===== Skills Manifest Commands =====
+
+/// Load a skills manifest and plan it against the installed skills
+async fn plan_manifest(
+    github: &GitHubService,
+    manifest_path: Option<&str>,
+) -> Result<SkillsPlan, String> {
+    let path = TeamService::resolve_manifest_path(manifest_path).map_err(|e| e.to_string())?;
+    let manifest = TeamService::load(&path).map_err(|e| e.to_string())?;
+
+    TeamService::plan(github, &manifest, &path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Show what applying a skills manifest (`skills.toml`) would install,
+/// update or remove
+#[tauri::command]
+pub async fn plan_skills_manifest(
+    state: State<'_, AppState>,
+    manifest_path: Option<String>,
+) -> Result<SkillsPlan, String> {
+    println!(
+        "[Rust] plan_skills_manifest called with: {:?}",
+        manifest_path
+    );
+    retry_connection(&state.github);
+
+    let plan = plan_manifest(&state.github, manifest_path.as_deref())
+        .await
+        .map_err(|e| {
+            println!("[Rust] plan_skills_manifest error: {}", e);
+            e
+        })?;
+
+    println!(
+        "[Rust] plan_skills_manifest: {} items from {}",
+        plan.items.len(),
+        plan.manifest_path
+    );
+    Ok(plan)
+}
+
+/// Install, update and remove skills so they match a skills manifest
+#[tauri::command]
+pub async fn apply_skills_manifest(
+    state: State<'_, AppState>,
+    manifest_path: Option<String>,
+) -> Result<SkillsApplyResult, String> {
+    println!(
+        "[Rust] apply_skills_manifest called with: {:?}",
+        manifest_path
+    );
+    retry_connection(&state.github);
+
+    let plan = plan_manifest(&state.github, manifest_path.as_deref()).await?;
+    Ok(apply_plan(&state.github, plan).await)
+}
+
+/// Carry out a plan item by item; a failing item does not stop the others
+async fn apply_plan(github: &GitHubService, plan: SkillsPlan) -> SkillsApplyResult {
+    // Skills from the same repository and commit share one tarball download
+    let mut archives = ArchiveCache::new();
+    let mut results = Vec::new();
+
+    for item in plan.items {
+        let result = match item.action {
+            PlanAction::Install => install_plan_item(github, &mut archives, &item).await,
+            PlanAction::Update => {
+                let source = SkillOrigin {
+                    repository: item.repository.clone().unwrap_or_default(),
+                    path: item.path.clone().unwrap_or_default(),
+                    git_ref: item.git_ref.clone(),
+                    commit_sha: item.target_commit.clone(),
+                    tree_sha: None,
+                };
+                run_skill_update(
+                    github,
+                    &mut archives,
+                    item.skill_name.clone(),
+                    &HashMap::new(),
+                    Some(source),
+                    item.content_hash.as_deref(),
+                )
+                .await
+                .map(|_| ())
+            }
+            PlanAction::Remove => uninstall_skill(item.skill_name.clone()),
+            PlanAction::Unchanged => Ok(()),
+            // Never overwrite a skill that came from somewhere else
+            PlanAction::Conflict => Err(item
+                .reason
+                .clone()
+                .unwrap_or_else(|| "Skipped conflicting skill".to_string())),
+        };
+
+        if let Err(e) = &result {
+            println!("[Rust] apply error for '{}': {}", item.skill_name, e);
+        }
+
+        results.push(PlanItemResult {
+            item,
+            success: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    let success = results.iter().all(|r| r.success);
+    println!(
+        "[Rust] apply complete: {} of {} succeeded",
+        results.iter().filter(|r| r.success).count(),
+        results.len()
+    );
+
+    SkillsApplyResult {
+        manifest_path: plan.manifest_path,
+        success,
+        results,
+    }
+}
+
+/// Install a planned skill at its target commit
+async fn install_plan_item(
+    github: &GitHubService,
+    archives: &mut ArchiveCache,
+    item: &PlanItem,
+) -> Result<(), String> {
+    let repository = item.repository.as_deref().unwrap_or_default();
+    let (owner, repo) = split_repository(repository)
+        .ok_or_else(|| format!("Invalid repository: {}", repository))?;
+
+    let (files, origin) = download_skill(
+        github,
+        archives,
+        owner,
+        repo,
+        item.path.as_deref().unwrap_or_default(),
+        item.git_ref.clone(),
+        item.target_commit.clone(),
+    )
+    .await?;
+
+    if let Some(expected) = &item.content_hash {
+        LockService::verify(&item.skill_name, &files, expected).map_err(|e| e.to_string())?;
+    }
+
+    SkillService::install_skill(&item.skill_name, files, Some(origin))
+        .map_err(|e| e.to_string())?;
+
+    let _ = snapshot_skill(&item.skill_name, VersionReason::Install);
+    Ok(())
+}
+
+// ===== Lockfile Commands =====
+
+/// Write a lockfile (`skills.lock`) pinning the commit and content hash of
+/// every installed skill with a known source
+#[tauri::command]
+pub fn generate_lockfile(lockfile_path: Option<String>) -> Result<Lockfile, String> {
+    println!("[Rust] generate_lockfile called with: {:?}", lockfile_path);
+
+    let path = LockService::resolve_path(lockfile_path.as_deref()).map_err(|e| e.to_string())?;
+    let lockfile = LockService::generate(&path).map_err(|e| e.to_string())?;
+
+    println!(
+        "[Rust] generate_lockfile: {} skills written to {}",
+        lockfile.skills.len(),
+        path.display()
+    );
+    Ok(lockfile)
+}
+
+/// Load a lockfile and plan it against the installed skills
+fn plan_lockfile_at(lockfile_path: Option<&str>) -> Result<SkillsPlan, String> {
+    let path = LockService::resolve_path(lockfile_path).map_err(|e| e.to_string())?;
+    let lockfile = LockService::load(&path).map_err(|e| e.to_string())?;
+    LockService::plan(&lockfile, &path).map_err(|e| e.to_string())
+}
+
+/// Show which skills would be installed or updated to match a lockfile
+#[tauri::command]
+pub fn plan_lockfile(lockfile_path: Option<String>) -> Result<SkillsPlan, String> {
+    println!("[Rust] plan_lockfile called with: {:?}", lockfile_path);
+    plan_lockfile_at(lockfile_path.as_deref())
+}
+
+/// Install the exact commits pinned in a lockfile, failing any skill whose
+/// downloaded content does not match the recorded hash
+#[tauri::command]
+pub async fn apply_lockfile(
+    state: State<'_, AppState>,
+    lockfile_path: Option<String>,
+) -> Result<SkillsApplyResult, String> {
+    println!("[Rust] apply_lockfile called with: {:?}", lockfile_path);
+    retry_connection(&state.github);
+
+    let plan = plan_lockfile_at(lockfile_path.as_deref())?;
+    Ok(apply_plan(&state.github, plan).await)
+}
+
+// ===== GitHub Token Commands =====
+
+/// Reload the tokens used by the GitHub client from the keychain, keeping
+/// a token discovered from the GitHub CLI or git when none is stored
+fn reload_credentials(github: &GitHubService) -> Result<(), String> {
+    let mut credentials = CredentialService::load().map_err(|e| e.to_string())?;
+    if credentials.token.is_none() {
+        let current = github.credentials();
+        if matches!(current.source, Some(TokenSource::Gh | TokenSource::Git)) {
+            credentials.token = current.token;
+            credentials.source = current.source;
+        }
+    }
+    github.set_credentials(credentials);
+    Ok(())
+}
+
+/// Look for a token of the GitHub CLI or git when no default token is
+/// configured. The commands run on a blocking thread, off the startup path.
+pub async fn discover_github_token(app: AppHandle) {
+    let state = app.state::<AppState>();
+    if state.github.credentials().token.is_some() {
+        return;
+    }
+
+    match tauri::async_runtime::spawn_blocking(CredentialService::discover_token).await {
+        Ok(Some((token, source))) => {
+            println!("[Rust] Using the GitHub token from {:?}", source);
+            state.github.set_discovered_token(token, source);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[Rust] Failed to discover a GitHub token: {}", e),
+    }
+}
+
+/// Report which GitHub tokens are configured
+#[tauri::command]
+pub fn get_github_auth_status(state: State<'_, AppState>) -> Result<GitHubAuthStatus, String> {
+    let credentials = state.github.credentials();
+    let mut repository_overrides: Vec<String> = credentials.repository_tokens.into_keys().collect();
+    repository_overrides.sort();
+
+    Ok(GitHubAuthStatus {
+        has_token: credentials.token.is_some(),
+        source: credentials.source,
+        repository_overrides,
+    })
+}
+
+/// Store a GitHub token in the OS keychain, either as the default token or
+/// for a single `owner/repo`
+#[tauri::command]
+pub fn set_github_token(
+    state: State<'_, AppState>,
+    token: String,
+    repository: Option<String>,
+) -> Result<(), String> {
+    println!("[Rust] set_github_token called for: {:?}", repository);
+    CredentialService::set_token(repository.as_deref(), &token).map_err(|e| e.to_string())?;
+    reload_credentials(&state.github)
+}
+
+/// Remove the default GitHub token or a repository's own token
+#[tauri::command]
+pub fn remove_github_token(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    repository: Option<String>,
+) -> Result<bool, String> {
+    println!("[Rust] remove_github_token called for: {:?}", repository);
+    let removed =
+        CredentialService::delete_token(repository.as_deref()).map_err(|e| e.to_string())?;
+    reload_credentials(&state.github)?;
+    // Fall back to the GitHub CLI or git, as at startup
+    tauri::async_runtime::spawn(discover_github_token(app));
+    Ok(removed)
+}
+
+/// Check a GitHub token and report its scopes and remaining quota. Without
+/// `token`, the stored token for `repository` (or the default one) is checked.
+#[tauri::command]
+pub async fn validate_github_token(
+    state: State<'_, AppState>,
+    token: Option<String>,
+    repository: Option<String>,
+) -> Result<TokenValidation, String> {
+    retry_connection(&state.github);
+    let token = match token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+    {
+        Some(token) => token,
+        None => {
+            let credentials = state.github.credentials();
+            repository
+                .as_deref()
+                .and_then(|r| credentials.repository_tokens.get(&r.trim().to_lowercase()))
+                .cloned()
+                .or(credentials.token)
+                .ok_or_else(|| "No GitHub token configured".to_string())?
+        }
+    };
+
+    state
+        .github
+        .validate_token(&token)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the core GitHub API quota of the default token. The quota seen on
+/// the last API response is returned unless `refresh` asks GitHub again.
+#[tauri::command]
+pub async fn get_rate_limit(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<RateLimitInfo, String> {
+    if !refresh.unwrap_or(false) {
+        if let Some(rate_limit) = state.github.rate_limit() {
+            return Ok(rate_limit);
+        }
+    }
+    retry_connection(&state.github);
+
+    state
+        .github
+        .refresh_rate_limit()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the app is in offline mode, and why
+#[tauri::command]
+pub fn get_offline_status(state: State<'_, AppState>) -> OfflineStatus {
+    let reason = state.github.offline_reason();
+    OfflineStatus {
+        offline: reason.is_some(),
+        reason,
+    }
+}
+
+/// Turn offline mode on or off. Turning it off also leaves an offline mode
+/// entered after connection failures.
+#[tauri::command]
+pub fn set_offline_mode(
+    state: State<'_, AppState>,
+    offline: bool,
+) -> Result<OfflineStatus, String> {
+    println!("[Rust] set_offline_mode called with: {}", offline);
+    ConfigService::set_offline(offline).map_err(|e| e.to_string())?;
+    state
+        .github
+        .set_offline(offline.then_some(OfflineReason::User));
+    Ok(get_offline_status(state))
+}
+
 // ===== Repository Management Commands =====
 
 /// List all configured repositories
@@ -394,108 +1709,225 @@ author: custom
             files.push((file.relative_path, file.content));
         }
     }
-    SkillService::install_skill(&skill_name, files).map_err(|e| e.to_string())
+    let result =
+        SkillService::install_skill(&skill_name, files, None).map_err(|e| e.to_string())?;
+    let _ = snapshot_skill(&skill_name, VersionReason::Install);
+    Ok(result)
 }
 
-/// Force sync repositories - clears cache and re-fetches from GitHub
+/// Sync a single repository and merge its skills into the catalog, leaving
+/// the skills of other repositories untouched. `force` re-scans it on GitHub
+/// even when a cache exists; the cache is only replaced once the scan succeeds.
 #[tauri::command]
-pub async fn force_sync_repositories(state: State<'_, AppState>) -> Result<SyncResult, String> {
-    println!("[Rust] force_sync_repositories called");
+pub async fn sync_repository(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    repo_id: String,
+    force: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<SyncResult, String> {
+    println!(
+        "[Rust] sync_repository called with: {} (force: {:?})",
+        repo_id, force
+    );
+    retry_connection(&state.github);
+    // Offline, the cache is served and kept even when a refresh was asked for
+    let force = force.unwrap_or(false) && !state.github.is_offline();
 
-    // Clear all cache first
-    let _ = CacheService::clear_all_cache();
-    println!("[Rust] Cache cleared");
+    let repositories = ConfigService::list_repositories().map_err(|e| e.to_string())?;
+    let repo = repositories
+        .iter()
+        .find(|r| r.id == repo_id)
+        .ok_or_else(|| format!("Repository not found: {}", repo_id))?;
+
+    let operation = start_sync_operation(&app, &state, operation_id, std::slice::from_ref(repo));
+    operation.emit(
+        ProgressStage::Started,
+        ProgressDetail {
+            total: Some(1),
+            ..Default::default()
+        },
+    );
+    let loaded = operation
+        .run(load_repository_with_progress(
+            &state,
+            &operation,
+            repo,
+            !force,
+            &AtomicU32::new(0),
+            1,
+        ))
+        .await;
+
+    let Some((report, skills)) = loaded else {
+        state.operations.finish(operation.id());
+        println!("[Rust] Sync {} cancelled", operation.id());
+        operation.emit(ProgressStage::Cancelled, ProgressDetail::default());
+        return Err(CANCELLED.to_string());
+    };
+
+    // Still registered while writing, so a background refresh does not interleave
+    save_repository_sync(&report, &skills);
+    if report.status != RepositorySyncStatus::Failed {
+        if let Ok(mut catalog) = state.catalog.lock() {
+            catalog.replace_repository(&repositories, repo, skills);
+        }
+    }
+    state.operations.finish(operation.id());
+
+    operation.emit(
+        ProgressStage::Finished,
+        ProgressDetail {
+            done: Some(1),
+            total: Some(1),
+            message: Some(format!("{} skills", report.skill_count)),
+            ..Default::default()
+        },
+    );
+
+    let result = sync_result(report.skill_count, vec![report], force);
+    println!("[Rust] sync_repository complete: {}", result.message);
+    Ok(result)
+}
+
+/// Force sync repositories - re-fetches from GitHub and replaces the cache,
+/// reporting progress under `operation_id`
+#[tauri::command]
+pub async fn force_sync_repositories(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    operation_id: Option<String>,
+) -> Result<SyncResult, String> {
+    println!("[Rust] force_sync_repositories called");
+    retry_connection(&state.github);
 
     // Load configured repositories
     let repositories = ConfigService::list_repositories().map_err(|e| e.to_string())?;
     println!("[Rust] Found {} repositories", repositories.len());
 
-    let mut all_skills = Vec::new();
-    let mut total_count = 0u32;
-    let mut errors = Vec::new();
-
-    // Default builtin repository URL
-    const BUILTIN_REPO: &str = "ComposioHQ/awesome-claude-skills";
-
-    for repo in &repositories {
-        // For builtin repo, still use builtin data (it's updated with app updates)
-        if repo.url == BUILTIN_REPO && repo.is_builtin {
-            let builtin_skills = data::load_builtin_skills();
-            let count = builtin_skills.len() as u32;
-            total_count += count;
-            all_skills.extend(builtin_skills);
-            println!(
-                "[Rust] Loaded {} builtin skills from {}",
-                count, BUILTIN_REPO
-            );
-            let _ = ConfigService::update_repository_sync(&repo.id, count);
-            continue;
-        }
+    let operation = start_sync_operation(&app, &state, operation_id, &repositories);
+    let result = sync_all(&state, &operation, &repositories, false).await;
+    state.operations.finish(operation.id());
+    let (total_count, reports) = result?;
 
-        // Parse owner/repo from url
-        let parts: Vec<&str> = repo.url.split('/').collect();
-        if parts.len() != 2 {
-            errors.push(format!("Invalid repository URL: {}", repo.url));
-            continue;
+    let result = sync_result(total_count, reports, true);
+    println!(
+        "[Rust] force_sync_repositories complete: {}",
+        result.message
+    );
+    Ok(result)
+}
+
+/// Event emitted when a background refresh changed the skills of repositories
+const CATALOG_CHANGED_EVENT: &str = "catalog-changed";
+
+/// Refresh the caches of repositories whose ref moved since they were
+/// scanned, checking only those past the cache TTL. Runs in the background at launch while the stale
+/// caches are served, and reports the repositories whose skills changed.
+pub async fn refresh_stale_repositories(app: AppHandle) {
+    let config = match ConfigService::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("[Rust] Failed to load config for cache refresh: {}", e);
+            return;
         }
+    };
+    let ttl = config.cache_ttl();
+    let state = app.state::<AppState>();
+    if state.github.is_offline() {
+        return;
+    }
 
-        let owner = parts[0];
-        let repo_name = parts[1];
-        println!("[Rust] Force fetching from: {}/{}", owner, repo_name);
-
-        match state
-            .github
-            .scan_skills(
-                owner,
-                repo_name,
-                repo.base_path.as_deref(),
-                repo.git_ref.as_deref(),
-            )
-            .await
-        {
-            Ok(skills) => {
-                let count = skills.len() as u32;
-                total_count += count;
+    let changed: Vec<RepositorySyncReport> = join_all(
+        config
+            .repositories
+            .iter()
+            .map(|repo| refresh_repository(&state, &config.repositories, repo, ttl)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if changed.is_empty() {
+        return;
+    }
+    println!(
+        "[Rust] Background refresh updated {} repositories",
+        changed.len()
+    );
+    let _ = app.emit(
+        CATALOG_CHANGED_EVENT,
+        CatalogChangedEvent {
+            repositories: changed,
+        },
+    );
+}
 
-                // Save to cache
-                let _ = CacheService::save_repo_cache(&repo.id, &skills);
+/// Rescan a repository whose cache is stale. Returns its report when its
+/// skills changed; repositories without a cache are left to the next sync.
+async fn refresh_repository(
+    state: &AppState,
+    repositories: &[Repository],
+    repo: &Repository,
+    ttl: Duration,
+) -> Option<RepositorySyncReport> {
+    // A sync in flight loads the repository anyway, and its results win
+    if state.operations.is_syncing(&repo.id) {
+        return None;
+    }
+    let cache = CacheService::load_repo_cache(&repo.id).ok().flatten()?;
+    if !is_cache_stale(&state.github, repo, &cache, ttl).await {
+        return None;
+    }
 
-                all_skills.extend(skills);
-                println!("[Rust] Found {} skills in {}/{}", count, owner, repo_name);
-                let _ = ConfigService::update_repository_sync(&repo.id, count);
-            }
-            Err(e) => {
-                let error_msg = format!("{}: {}", repo.url, e);
-                println!("[Rust] Error scanning {}/{}: {}", owner, repo_name, e);
-                errors.push(error_msg);
-            }
-        }
+    println!("[Rust] Refreshing stale cache of {}", repo.url);
+    let (report, skills) = load_repository_skills(&state.github, repo, false, None).await;
+    if report.status == RepositorySyncStatus::Failed || state.operations.is_syncing(&repo.id) {
+        return None;
     }
+    save_repository_sync(&report, &skills);
 
-    // Update in-memory cache
-    let mut cache = state.skills_cache.lock().map_err(|e| e.to_string())?;
-    *cache = all_skills;
+    let skill_ids = |skills: &[Skill]| skills.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+    if report.commit_sha == cache.commit_sha && skill_ids(&skills) == skill_ids(&cache.skills) {
+        return None;
+    }
 
-    let message = if errors.is_empty() {
-        format!(
-            "Force synced {} skills from {} repositories",
-            total_count,
-            repositories.len()
-        )
-    } else {
-        format!(
-            "Force synced {} skills with {} errors: {}",
-            total_count,
-            errors.len(),
-            errors.join("; ")
-        )
-    };
+    if let Ok(mut catalog) = state.catalog.lock() {
+        catalog.replace_repository(repositories, repo, skills);
+    }
+    Some(report)
+}
 
-    println!("[Rust] force_sync_repositories complete: {}", message);
+/// Whether a cache older than `ttl` was built from a commit that is no
+/// longer the head of the repository's ref. GitHub is only asked once the
+/// TTL has passed; a cache whose branch has not moved is trusted for
+/// another `ttl`.
+async fn is_cache_stale(
+    github: &GitHubService,
+    repo: &Repository,
+    cache: &RepoCache,
+    ttl: Duration,
+) -> bool {
+    if !cache.is_expired(ttl) {
+        return false;
+    }
+    let Some((owner, repo_name)) = split_repository(&repo.url) else {
+        return false;
+    };
 
-    Ok(SyncResult {
-        success: errors.is_empty(),
-        skills_found: total_count,
-        message,
-    })
+    match github
+        .resolve_commit_sha(owner, repo_name, repo.git_ref.as_deref())
+        .await
+    {
+        Ok(head) if cache.commit_sha.as_deref() == Some(head.as_str()) => {
+            let _ = CacheService::save_repo_cache(&repo.id, &cache.skills, Some(&head));
+            false
+        }
+        Ok(_) => true,
+        Err(e) => {
+            println!("[Rust] Could not check the head of {}: {}", repo.url, e);
+            false
+        }
+    }
 }
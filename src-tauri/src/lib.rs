@@ -4,24 +4,89 @@ mod models;
 mod services;
 
 use commands::AppState;
+use services::{CacheService, ConfigService, SkillService, TrashService};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Drop half-built installs left behind by a previous crash
+    if let Err(e) = SkillService::cleanup_staging() {
+        eprintln!("[Rust] Failed to clean up staging directory: {}", e);
+    }
+
+    // Permanently delete uninstalled skills past their retention period
+    if let Ok(config) = ConfigService::load() {
+        if let Err(e) = TrashService::purge_expired(config.trash_retention_days) {
+            eprintln!("[Rust] Failed to purge trash: {}", e);
+        }
+
+        // Keep the blob cache of downloaded skill files within its size limit
+        if let Err(e) = CacheService::prune_blobs(config.blob_cache_max_bytes()) {
+            eprintln!("[Rust] Failed to prune the blob cache: {}", e);
+        }
+        // Drop GitHub responses that are old or over the size limit
+        if let Err(e) = CacheService::prune_http_entries(
+            services::cache::HTTP_CACHE_MAX_AGE,
+            config.http_cache_max_bytes(),
+        ) {
+            eprintln!("[Rust] Failed to prune the HTTP cache: {}", e);
+        }
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(AppState::new())
+        .setup(|app| {
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                commands::discover_github_token(handle.clone()).await;
+                // Stale caches are served right away and refreshed behind them
+                commands::refresh_stale_repositories(handle).await;
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::fetch_store_skills,
             commands::sync_repositories,
             commands::force_sync_repositories,
+            commands::sync_repository,
+            commands::cancel_operation,
             commands::get_cached_skills,
+            commands::get_skill,
+            commands::get_repository_skills,
             commands::list_installed_skills,
             commands::is_skill_installed,
             commands::install_skill,
             commands::uninstall_skill,
+            commands::disable_skill,
+            commands::enable_skill,
+            commands::list_profiles,
+            commands::create_profile,
+            commands::update_profile,
+            commands::delete_profile,
+            commands::activate_profile,
+            commands::list_skill_versions,
+            commands::rollback_skill,
+            commands::list_trash,
+            commands::restore_from_trash,
+            commands::empty_trash,
+            commands::check_skill_updates,
+            commands::preview_skill_update,
+            commands::update_skill,
+            commands::plan_skills_manifest,
+            commands::apply_skills_manifest,
+            commands::generate_lockfile,
+            commands::plan_lockfile,
+            commands::apply_lockfile,
+            commands::get_github_auth_status,
+            commands::set_github_token,
+            commands::remove_github_token,
+            commands::validate_github_token,
+            commands::get_rate_limit,
+            commands::get_offline_status,
+            commands::set_offline_mode,
             commands::get_skills_directory,
             commands::get_skill_content,
             commands::list_repositories,
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Skill category
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -50,6 +51,38 @@ pub struct InstalledSkill {
     pub description: String,
     pub path: String,
     pub installed_at: String,
+    /// Disabled skills are parked outside the skills directory
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SkillOrigin>,
+}
+
+/// Where an installed skill was downloaded from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillOrigin {
+    /// Source repository as `owner/repo`
+    pub repository: String,
+    /// Skill directory inside the repository (empty for the repository root)
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+    /// Git tree SHA of the skill directory at `commit_sha`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_sha: Option<String>,
+}
+
+/// Provenance manifest written as `.myskills.json` into every installed skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillManifest {
+    /// `None` for skills created locally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SkillOrigin>,
+    pub installed_at: String,
+    pub app_version: String,
+    /// SHA-256 of each installed file, keyed by relative path
+    pub files: BTreeMap<String, String>,
 }
 
 /// Repository configuration
@@ -81,6 +114,486 @@ pub struct GitHubContent {
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub download_url: Option<String>,
+    #[serde(default)]
+    pub sha: Option<String>,
+}
+
+/// GitHub API response for a commit
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubCommit {
+    pub sha: String,
+    pub commit: GitHubCommitDetail,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubCommitDetail {
+    pub tree: GitHubTreeRef,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubTreeRef {
+    pub sha: String,
+}
+
+/// GitHub API response for a (recursive) git tree
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubTree {
+    pub sha: String,
+    pub tree: Vec<GitHubTreeEntry>,
+    /// Set when the tree was too large to be returned completely
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubTreeEntry {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub sha: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+/// GitHub API response for the authenticated user
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubUser {
+    pub login: String,
+}
+
+/// GitHub API response for `/rate_limit`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubRateLimit {
+    pub resources: GitHubRateLimitResources,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubRateLimitResources {
+    pub core: GitHubRateLimitEntry,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubRateLimitEntry {
+    pub limit: u32,
+    pub remaining: u32,
+    #[serde(default)]
+    pub used: u32,
+    /// Unix timestamp when the quota resets
+    pub reset: i64,
+}
+
+/// GitHub API quota
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    pub limit: u32,
+    pub remaining: u32,
+    pub used: u32,
+    pub reset_at: String,
+}
+
+/// Result of checking a GitHub token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenValidation {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login: Option<String>,
+    /// OAuth scopes of a classic token (empty for fine-grained tokens)
+    pub scopes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Where the default GitHub token came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenSource {
+    /// Stored in the OS keychain from the settings page
+    Keychain,
+    /// `GH_TOKEN` or `GITHUB_TOKEN` environment variable
+    Env,
+    /// GitHub CLI login (`gh auth login`)
+    Gh,
+    /// git credential helper for github.com
+    Git,
+}
+
+/// Which GitHub tokens are configured; the tokens themselves are never returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubAuthStatus {
+    pub has_token: bool,
+    /// Source of the default token, if any
+    pub source: Option<TokenSource>,
+    /// Repositories (`owner/repo`) with their own token
+    pub repository_overrides: Vec<String>,
+}
+
+/// Update status of an installed skill compared with its source repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillUpdateInfo {
+    pub skill_name: String,
+    pub repository: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_sha: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_sha: Option<String>,
+    pub has_update: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How to resolve a file that was changed both locally and upstream
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    /// Merge line by line and leave conflict markers where both sides overlap
+    #[default]
+    Merge,
+    /// Keep the local version
+    Mine,
+    /// Take the upstream version
+    Theirs,
+}
+
+/// What happened to a single file during a merge
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileMergeStatus {
+    /// Only changed upstream, upstream version taken
+    Updated,
+    /// Only changed locally, local version kept
+    Local,
+    /// Changed on both sides without overlap, merged automatically
+    Merged,
+    /// Removed upstream and not modified locally
+    Removed,
+    /// Changed on both sides in overlapping ways
+    Conflict,
+}
+
+/// Merge result for a single file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMergeResult {
+    pub path: String,
+    pub status: FileMergeStatus,
+    /// Resolution applied to a conflict
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<ConflictResolution>,
+    /// Number of conflict marker blocks written into the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict_markers: Option<usize>,
+}
+
+/// Result of updating (or previewing an update of) an installed skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillUpdateResult {
+    pub skill_name: String,
+    /// Installed path, `None` when only previewing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Files that changed locally, upstream or both
+    pub files: Vec<FileMergeResult>,
+    pub has_conflicts: bool,
+}
+
+/// Why a version snapshot of a skill was taken
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionReason {
+    Install,
+    Update,
+    Uninstall,
+    Rollback,
+}
+
+/// A saved version of an installed skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillVersion {
+    pub id: String,
+    pub skill_name: String,
+    pub created_at: String,
+    pub reason: VersionReason,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SkillOrigin>,
+    pub file_count: u32,
+    /// Hash over all files, used to skip identical snapshots
+    pub digest: String,
+}
+
+/// A skill that was uninstalled into the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub skill_name: String,
+    pub deleted_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SkillOrigin>,
+}
+
+/// A named set of skills that should be enabled together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillProfile {
+    pub id: String,
+    pub name: String,
+    /// Names of the installed skills to enable; all others are disabled
+    pub skills: Vec<String>,
+}
+
+/// Result of switching to a skill profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileActivation {
+    pub profile_id: String,
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    /// Skills in the profile that are not installed
+    pub missing: Vec<String>,
+}
+
+/// Declarative skills manifest (`skills.toml`) shared by a team
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TeamManifest {
+    #[serde(default)]
+    pub repositories: Vec<ManifestRepository>,
+    #[serde(default)]
+    pub skills: Vec<ManifestSkill>,
+    /// Remove installed skills with a recorded source that are not listed
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// A repository referenced by a skills manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestRepository {
+    /// Repository as `owner/repo`
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+}
+
+/// A skill listed in a skills manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSkill {
+    /// Repository as `owner/repo`, must be listed under `repositories`
+    pub repository: String,
+    /// Skill directory relative to the repository's `base_path`
+    #[serde(default)]
+    pub path: String,
+    /// Install exactly this commit instead of the head of `git_ref`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// What applying a skills manifest would do to one skill
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanAction {
+    Install,
+    Update,
+    Remove,
+    Unchanged,
+    /// A skill with the same name exists from another source and is left alone
+    Conflict,
+}
+
+/// One planned change of a skills manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanItem {
+    pub skill_name: String,
+    pub action: PlanAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_commit: Option<String>,
+    /// Content hash the downloaded files must match (from a lockfile)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Changes needed to bring `~/.claude/skills` in line with a skills manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillsPlan {
+    pub manifest_path: String,
+    pub items: Vec<PlanItem>,
+}
+
+/// Outcome of applying one planned change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanItemResult {
+    pub item: PlanItem,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of applying a skills manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillsApplyResult {
+    pub manifest_path: String,
+    pub success: bool,
+    pub results: Vec<PlanItemResult>,
+}
+
+/// Lockfile (`skills.lock`) pinning the exact version of each installed skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    pub generated_at: String,
+    #[serde(default)]
+    pub skills: Vec<LockedSkill>,
+}
+
+/// A skill pinned in a lockfile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedSkill {
+    pub name: String,
+    /// Repository as `owner/repo`
+    pub repository: String,
+    #[serde(default)]
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Resolved commit SHA the skill is installed from
+    pub commit: String,
+    /// Hash over the upstream files of the skill at `commit`
+    pub content_hash: String,
+}
+
+/// Kind of a long-running operation that reports progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Sync,
+    Install,
+}
+
+/// Stage of an operation, or of one repository or file within it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressStage {
+    Started,
+    Progress,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+/// What a progress event is about; unset fields do not apply to it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProgressDetail {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Bytes downloaded so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    /// Repositories (syncs) or files (installs) done so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// When a sync paused by the rate limit resumes (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_at: Option<String>,
+}
+
+/// Payload of the `operation-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub operation_id: String,
+    pub kind: OperationKind,
+    pub stage: ProgressStage,
+    #[serde(flatten)]
+    pub detail: ProgressDetail,
+}
+
+/// Where the skills of a repository came from during a sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepositorySyncStatus {
+    /// Bundled with the app
+    Builtin,
+    /// Read from the local cache
+    Cached,
+    /// Freshly scanned on GitHub
+    Scanned,
+    Failed,
+}
+
+/// Why a repository failed to sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncErrorKind {
+    InvalidRepository,
+    NotFound,
+    RateLimited,
+    Forbidden,
+    Network,
+    Offline,
+    Other,
+}
+
+/// Why GitHub is not contacted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineReason {
+    /// Turned on in the settings
+    User,
+    /// Entered after repeated requests could not reach GitHub
+    ConnectionFailures,
+}
+
+/// Whether the app works from its caches only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineStatus {
+    pub offline: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<OfflineReason>,
+}
+
+/// Outcome of syncing one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositorySyncReport {
+    pub repository_id: String,
+    /// `owner/repo`
+    pub repository: String,
+    pub status: RepositorySyncStatus,
+    pub skill_count: u32,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<SyncErrorKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Age of the cache the skills were read from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_age_secs: Option<u64>,
+    /// Commit the skills were scanned from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+}
+
+/// Repositories whose skills changed in a background refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogChangedEvent {
+    pub repositories: Vec<RepositorySyncReport>,
 }
 
 /// Sync result
@@ -89,4 +602,6 @@ pub struct SyncResult {
     pub success: bool,
     pub skills_found: u32,
     pub message: String,
+    /// One entry per repository, in configuration order
+    pub repositories: Vec<RepositorySyncReport>,
 }
@@ -0,0 +1,323 @@
+use crate::models::{ProgressDetail, ProgressStage};
+use crate::services::github::{GitHubError, GitHubService};
+use crate::services::operations::Operation;
+use crate::services::skill::{SkillError, SkillService};
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+    #[error(transparent)]
+    Skill(#[from] SkillError),
+    #[error("Not found in archive: {0}")]
+    NotFound(String),
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+/// Repository tarballs downloaded during one batch of installs, so skills
+/// from the same repository and commit share a single download. The
+/// archives are deleted when the cache is dropped.
+#[derive(Default)]
+pub struct ArchiveCache {
+    dir: Option<PathBuf>,
+    archives: HashMap<String, PathBuf>,
+    /// Operation that downloads and extracted files are reported to
+    operation: Option<Arc<Operation>>,
+}
+
+impl ArchiveCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache reporting its downloads to `operation`
+    pub fn with_operation(operation: Arc<Operation>) -> Self {
+        Self {
+            dir: None,
+            archives: HashMap::new(),
+            operation: Some(operation),
+        }
+    }
+
+    pub fn operation(&self) -> Option<&Operation> {
+        self.operation.as_deref()
+    }
+
+    /// Get the files of a skill directory from the repository tarball at `commit_sha`
+    pub async fn skill_files(
+        &mut self,
+        github: &GitHubService,
+        owner: &str,
+        repo: &str,
+        commit_sha: &str,
+        skill_path: &str,
+    ) -> Result<Vec<(String, Vec<u8>)>, ArchiveError> {
+        let key = format!("{}/{}@{}", owner, repo, commit_sha);
+
+        let archive = match self.archives.get(&key) {
+            Some(archive) => archive.clone(),
+            None => {
+                let dir = match &self.dir {
+                    Some(dir) => dir.clone(),
+                    None => {
+                        // Leftovers of a crash are removed with the rest of the staging area
+                        let dir = SkillService::create_staging_path("archives")?;
+                        self.dir = Some(dir.clone());
+                        dir
+                    }
+                };
+
+                let archive = dir.join(format!("{}.tar.gz", self.archives.len()));
+                println!("[Rust] downloading tarball: {}", key);
+                github
+                    .download_tarball(owner, repo, commit_sha, &archive, self.operation())
+                    .await?;
+                self.archives.insert(key, archive.clone());
+                archive
+            }
+        };
+
+        let skill_path = skill_path.trim_matches('/').to_string();
+        let operation = self.operation.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            extract_skill_files(&archive, &skill_path, operation.as_deref())
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        Ok(files)
+    }
+}
+
+impl Drop for ArchiveCache {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Extract the files below `skill_path` from a gzipped repository tarball,
+/// decompressing it as a stream rather than loading it into memory.
+///
+/// Symlinks are returned as files holding the link target, as git stores
+/// them and as the contents API serves them. Entries with `..` are skipped.
+fn extract_skill_files(
+    archive: &Path,
+    skill_path: &str,
+    progress: Option<&Operation>,
+) -> Result<Vec<(String, Vec<u8>)>, ArchiveError> {
+    let mut tar = tar::Archive::new(GzDecoder::new(BufReader::new(File::open(archive)?)));
+    let mut files = Vec::new();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_symlink() {
+            continue;
+        }
+
+        let path = entry.path()?.to_string_lossy().replace('\\', "/");
+
+        // Every path is prefixed with an `owner-repo-sha/` directory
+        let Some((_, repo_path)) = path.split_once('/') else {
+            continue;
+        };
+        let relative = if skill_path.is_empty() {
+            repo_path
+        } else {
+            match repo_path
+                .strip_prefix(skill_path)
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                Some(relative) => relative,
+                None => continue,
+            }
+        };
+        if relative.split('/').any(|segment| segment == "..") {
+            continue;
+        }
+
+        // Extraction runs on a blocking thread that cancelling does not stop
+        if progress.is_some_and(|p| p.is_cancelled()) {
+            return Err(ArchiveError::Cancelled);
+        }
+
+        let content = if entry_type.is_symlink() {
+            match entry.link_name_bytes() {
+                Some(target) => target.into_owned(),
+                None => continue,
+            }
+        } else {
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut content)?;
+            content
+        };
+
+        if let Some(progress) = progress {
+            progress.emit(
+                ProgressStage::Progress,
+                ProgressDetail {
+                    file: Some(relative.to_string()),
+                    bytes: Some(content.len() as u64),
+                    done: Some(files.len() as u32 + 1),
+                    ..Default::default()
+                },
+            );
+        }
+        files.push((relative.to_string(), content));
+    }
+
+    if files.is_empty() {
+        return Err(ArchiveError::NotFound(skill_path.to_string()));
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::pairs;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    enum Entry<'a> {
+        File(&'a str, &'a str),
+        Symlink(&'a str, &'a str),
+    }
+
+    /// Write a gzipped tarball laid out like a GitHub repository tarball
+    fn archive(name: &str, entries: &[Entry]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "myskills-archive-{}-{}.tar.gz",
+            std::process::id(),
+            name
+        ));
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            File::create(&path).unwrap(),
+            Compression::fast(),
+        ));
+
+        for entry in entries {
+            let (path, data, entry_type, link) = match entry {
+                Entry::File(path, content) => {
+                    (path, content.as_bytes(), tar::EntryType::Regular, None)
+                }
+                Entry::Symlink(path, target) => {
+                    (path, &[][..], tar::EntryType::Symlink, Some(target))
+                }
+            };
+            let mut header = tar::Header::new_gnu();
+            // Set the name directly, as `set_path` refuses `..`
+            let name = format!("owner-repo-abc123/{}", path);
+            header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(entry_type);
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            if let Some(target) = link {
+                header.set_link_name(target).unwrap();
+            }
+            header.set_cksum();
+            builder.append(&header, data).unwrap();
+        }
+
+        builder.into_inner().unwrap().finish().unwrap();
+        path
+    }
+
+    fn extract(
+        name: &str,
+        entries: &[Entry],
+        skill_path: &str,
+    ) -> Result<Vec<(String, String)>, ArchiveError> {
+        let path = archive(name, entries);
+        let files = extract_skill_files(&path, skill_path, None);
+        let _ = fs::remove_file(&path);
+        Ok(files?
+            .into_iter()
+            .map(|(path, content)| (path, String::from_utf8(content).unwrap()))
+            .collect())
+    }
+
+    #[test]
+    fn strips_the_archive_and_skill_prefixes() {
+        let files = extract(
+            "prefix",
+            &[
+                Entry::File("README.md", "readme"),
+                Entry::File("skills/demo/SKILL.md", "# Demo"),
+                Entry::File("skills/demo/scripts/run.sh", "echo"),
+                Entry::File("skills/demo-extra/SKILL.md", "# Other"),
+            ],
+            "skills/demo",
+        )
+        .unwrap();
+
+        assert_eq!(
+            files,
+            pairs(&[("SKILL.md", "# Demo"), ("scripts/run.sh", "echo")])
+        );
+    }
+
+    #[test]
+    fn extracts_the_whole_repository_for_an_empty_skill_path() {
+        let files = extract("root", &[Entry::File("SKILL.md", "# Root")], "").unwrap();
+
+        assert_eq!(files, pairs(&[("SKILL.md", "# Root")]));
+    }
+
+    #[test]
+    fn skips_entries_that_leave_the_skill_directory() {
+        let files = extract(
+            "parent",
+            &[
+                Entry::File("skills/demo/SKILL.md", "# Demo"),
+                Entry::File("skills/demo/../../escape.md", "nope"),
+            ],
+            "skills/demo",
+        )
+        .unwrap();
+
+        assert_eq!(files, pairs(&[("SKILL.md", "# Demo")]));
+    }
+
+    #[test]
+    fn returns_symlinks_as_their_target() {
+        let files = extract(
+            "symlink",
+            &[
+                Entry::File("skills/demo/SKILL.md", "# Demo"),
+                Entry::Symlink("skills/demo/shared.md", "../shared/notes.md"),
+            ],
+            "skills/demo",
+        )
+        .unwrap();
+
+        assert_eq!(
+            files,
+            pairs(&[("SKILL.md", "# Demo"), ("shared.md", "../shared/notes.md")])
+        );
+    }
+
+    #[test]
+    fn reports_a_missing_skill_directory() {
+        let result = extract(
+            "missing",
+            &[Entry::File("README.md", "readme")],
+            "skills/demo",
+        );
+
+        assert!(matches!(result, Err(ArchiveError::NotFound(path)) if path == "skills/demo"));
+    }
+}
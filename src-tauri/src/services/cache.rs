@@ -1,6 +1,11 @@
 use crate::models::Skill;
-use std::fs;
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +16,51 @@ pub enum CacheError {
     Parse(#[from] serde_json::Error),
     #[error("Cache directory not found")]
     CacheDirNotFound,
+    #[error("Invalid blob SHA: {0}")]
+    InvalidBlobSha(String),
+}
+
+/// Cached GitHub responses not used for this long are deleted
+pub const HTTP_CACHE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A GitHub response stored with the validators needed to revalidate it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpCacheEntry {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// Skills scanned from a repository, with when and from which commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoCache {
+    pub fetched_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+    pub skills: Vec<Skill>,
+}
+
+impl RepoCache {
+    /// How long ago the skills were fetched
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.fetched_at).to_std().unwrap_or_default()
+    }
+
+    /// Whether the skills were fetched more than `ttl` ago
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.age() >= ttl
+    }
+}
+
+/// Repository caches written before fetch times were recorded hold only the skills
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredRepoCache {
+    Current(RepoCache),
+    Legacy(Vec<Skill>),
 }
 
 pub struct CacheService;
@@ -37,8 +87,178 @@ impl CacheService {
         Ok(cache_dir.join(format!("{}.json", safe_id)))
     }
 
+    /// Get the file a response for `url` is cached in
+    fn get_http_cache_path(url: &str) -> Result<PathBuf, CacheError> {
+        let http_dir = Self::get_cache_dir()?.join("http");
+        if !http_dir.exists() {
+            fs::create_dir_all(&http_dir)?;
+        }
+        Ok(http_dir.join(format!("{:x}.json", Sha256::digest(url.as_bytes()))))
+    }
+
+    /// Load the cached response for a URL, marking it as recently used
+    pub fn load_http_entry(url: &str) -> Result<Option<HttpCacheEntry>, CacheError> {
+        let cache_path = Self::get_http_cache_path(url)?;
+
+        if !cache_path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&cache_path)?;
+        let entry: HttpCacheEntry = serde_json::from_str(&content)?;
+        touch(&cache_path)?;
+
+        // Guard against a hash collision serving another URL's body
+        Ok(Some(entry).filter(|e| e.url == url))
+    }
+
+    /// Save a response together with its ETag and Last-Modified values
+    pub fn save_http_entry(entry: &HttpCacheEntry) -> Result<(), CacheError> {
+        let cache_path = Self::get_http_cache_path(&entry.url)?;
+        let content = serde_json::to_string(entry)?;
+        fs::write(&cache_path, content)?;
+        Ok(())
+    }
+
+    /// Delete cached responses not used for `max_age`, then the least
+    /// recently used ones until the rest take at most `max_bytes`. Returns
+    /// the number of bytes freed.
+    pub fn prune_http_entries(max_age: Duration, max_bytes: u64) -> Result<u64, CacheError> {
+        let http_dir = Self::get_cache_dir()?.join("http");
+        if !http_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&http_dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                entries.push((used, metadata.len(), entry.path()));
+            }
+        }
+
+        let mut freed = 0;
+        let now = SystemTime::now();
+        let mut recent = Vec::with_capacity(entries.len());
+        for (used, size, path) in entries {
+            if now.duration_since(used).unwrap_or_default() > max_age {
+                fs::remove_file(&path)?;
+                freed += size;
+            } else {
+                recent.push((used, size, path));
+            }
+        }
+
+        Ok(freed + remove_least_recently_used(recent, max_bytes)?)
+    }
+
+    /// Get the file the contents with git blob SHA `sha` are stored in.
+    /// Blobs are spread over directories named after the first two hex
+    /// digits, like the objects of a git repository.
+    fn get_blob_path(sha: &str) -> Result<PathBuf, CacheError> {
+        if sha.len() != 40 || !sha.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(CacheError::InvalidBlobSha(sha.to_string()));
+        }
+        let sha = sha.to_ascii_lowercase();
+        let (prefix, rest) = sha.split_at(2);
+        Ok(Self::get_cache_dir()?.join("blobs").join(prefix).join(rest))
+    }
+
+    /// Git's SHA-1 of a blob with these contents, as GitHub lists it for a file
+    pub fn git_blob_sha(bytes: &[u8]) -> String {
+        let mut hasher = Sha1::new();
+        hasher.update(format!("blob {}\0", bytes.len()));
+        hasher.update(bytes);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Load downloaded file contents by git blob SHA, marking them as
+    /// recently used. A blob whose contents no longer match its SHA is
+    /// deleted and reported as missing, so it is downloaded again.
+    pub fn load_blob(sha: &str) -> Result<Option<Vec<u8>>, CacheError> {
+        let blob_path = Self::get_blob_path(sha)?;
+
+        if !blob_path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&blob_path)?;
+        if !Self::git_blob_sha(&bytes).eq_ignore_ascii_case(sha) {
+            eprintln!("[Rust] Cached blob {} is corrupt, removing it", sha);
+            fs::remove_file(&blob_path)?;
+            return Ok(None);
+        }
+        touch(&blob_path)?;
+        Ok(Some(bytes))
+    }
+
+    /// Load files by `(path, git blob SHA)`, or `None` unless every one of
+    /// them can be read from the blob cache
+    pub fn load_blobs(blobs: &[(String, String)]) -> Option<Vec<(String, Vec<u8>)>> {
+        let all_cached = blobs
+            .iter()
+            .all(|(_, sha)| Self::get_blob_path(sha).is_ok_and(|path| path.exists()));
+        if !all_cached {
+            return None;
+        }
+
+        blobs
+            .iter()
+            .map(|(path, sha)| Some((path.clone(), Self::load_blob(sha).ok()??)))
+            .collect()
+    }
+
+    /// Store downloaded file contents under their git blob SHA, which is returned
+    pub fn save_blob(bytes: &[u8]) -> Result<String, CacheError> {
+        let sha = Self::git_blob_sha(bytes);
+        let blob_path = Self::get_blob_path(&sha)?;
+
+        if blob_path.exists() {
+            touch(&blob_path)?;
+            return Ok(sha);
+        }
+
+        if let Some(parent) = blob_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Written aside and renamed, so a blob is never read half-written
+        let partial = blob_path.with_extension("partial");
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, &blob_path)?;
+        Ok(sha)
+    }
+
+    /// Delete the least recently used blobs until the blob cache takes at
+    /// most `max_bytes`. Returns the number of bytes freed.
+    pub fn prune_blobs(max_bytes: u64) -> Result<u64, CacheError> {
+        let blob_dir = Self::get_cache_dir()?.join("blobs");
+        if !blob_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut blobs = Vec::new();
+        for prefix in fs::read_dir(&blob_dir)? {
+            let prefix = prefix?.path();
+            if !prefix.is_dir() {
+                continue;
+            }
+            for blob in fs::read_dir(&prefix)? {
+                let blob = blob?;
+                let metadata = blob.metadata()?;
+                if metadata.is_file() {
+                    let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    blobs.push((used, metadata.len(), blob.path()));
+                }
+            }
+        }
+
+        remove_least_recently_used(blobs, max_bytes)
+    }
+
     /// Load cached skills for a repository
-    pub fn load_repo_cache(repo_id: &str) -> Result<Option<Vec<Skill>>, CacheError> {
+    pub fn load_repo_cache(repo_id: &str) -> Result<Option<RepoCache>, CacheError> {
         let cache_path = Self::get_repo_cache_path(repo_id)?;
 
         if !cache_path.exists() {
@@ -46,14 +266,34 @@ impl CacheService {
         }
 
         let content = fs::read_to_string(&cache_path)?;
-        let skills: Vec<Skill> = serde_json::from_str(&content)?;
-        Ok(Some(skills))
+        let cache = match serde_json::from_str(&content)? {
+            StoredRepoCache::Current(cache) => cache,
+            StoredRepoCache::Legacy(skills) => RepoCache {
+                // The file was last written when the skills were fetched
+                fetched_at: fs::metadata(&cache_path)?
+                    .modified()
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or(DateTime::<Utc>::UNIX_EPOCH),
+                commit_sha: None,
+                skills,
+            },
+        };
+        Ok(Some(cache))
     }
 
-    /// Save skills cache for a repository
-    pub fn save_repo_cache(repo_id: &str, skills: &[Skill]) -> Result<(), CacheError> {
+    /// Save the skills of a repository, fetched now from `commit_sha`
+    pub fn save_repo_cache(
+        repo_id: &str,
+        skills: &[Skill],
+        commit_sha: Option<&str>,
+    ) -> Result<(), CacheError> {
         let cache_path = Self::get_repo_cache_path(repo_id)?;
-        let content = serde_json::to_string_pretty(skills)?;
+        let cache = RepoCache {
+            fetched_at: Utc::now(),
+            commit_sha: commit_sha.map(str::to_string),
+            skills: skills.to_vec(),
+        };
+        let content = serde_json::to_string_pretty(&cache)?;
         fs::write(&cache_path, content)?;
         Ok(())
     }
@@ -67,7 +307,9 @@ impl CacheService {
         Ok(())
     }
 
-    /// Clear all cache
+    /// Clear all cached skill listings. Cached HTTP responses are kept, as
+    /// they are revalidated with GitHub every time they are used, and so are
+    /// blobs, which never go stale.
     pub fn clear_all_cache() -> Result<(), CacheError> {
         let cache_dir = Self::get_cache_dir()?;
         if cache_dir.exists() {
@@ -82,3 +324,37 @@ impl CacheService {
         Ok(())
     }
 }
+
+/// Mark a cache file as used now. The modification time orders cache files
+/// for garbage collection.
+fn touch(path: &Path) -> Result<(), CacheError> {
+    File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())?;
+    Ok(())
+}
+
+/// Delete the least recently used of `(last used, size, path)` files until
+/// the rest take at most `max_bytes`. Returns the number of bytes freed.
+fn remove_least_recently_used(
+    mut files: Vec<(SystemTime, u64, PathBuf)>,
+    max_bytes: u64,
+) -> Result<u64, CacheError> {
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return Ok(0);
+    }
+
+    files.sort_by_key(|(used, _, _)| *used);
+    let mut freed = 0;
+    for (_, size, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        fs::remove_file(&path)?;
+        total -= size;
+        freed += size;
+    }
+    Ok(freed)
+}
@@ -0,0 +1,203 @@
+use crate::models::{Repository, Skill};
+use std::collections::HashMap;
+
+/// The skills of the store in configuration order, indexed by id and by repository
+#[derive(Debug, Default)]
+pub struct SkillCatalog {
+    skills: Vec<Skill>,
+    /// Position in `skills` of each skill id
+    by_id: HashMap<String, usize>,
+    /// Positions in `skills` of the skills of each lowercased `owner/repo`
+    by_repository: HashMap<String, Vec<usize>>,
+}
+
+impl SkillCatalog {
+    pub fn new(skills: Vec<Skill>) -> Self {
+        let mut catalog = Self {
+            skills,
+            ..Default::default()
+        };
+        catalog.reindex();
+        catalog
+    }
+
+    pub fn skills(&self) -> &[Skill] {
+        &self.skills
+    }
+
+    /// Find a skill by id
+    pub fn get(&self, id: &str) -> Option<&Skill> {
+        self.by_id.get(id).map(|&index| &self.skills[index])
+    }
+
+    /// Skills of a repository (`owner/repo`, case-insensitive)
+    pub fn repository_skills(&self, repository: &str) -> Vec<&Skill> {
+        self.by_repository
+            .get(&repository.to_lowercase())
+            .map(|indices| indices.iter().map(|&index| &self.skills[index]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Replace the skills of one repository. The new skills go where a full
+    /// sync would put them, before the skills of repositories configured after it.
+    pub fn replace_repository(
+        &mut self,
+        repositories: &[Repository],
+        repo: &Repository,
+        skills: Vec<Skill>,
+    ) {
+        let position = |url: &str| {
+            repositories
+                .iter()
+                .position(|r| r.url.eq_ignore_ascii_case(url))
+        };
+        let own = position(&repo.url);
+
+        self.skills
+            .retain(|skill| !skill.repository.eq_ignore_ascii_case(&repo.url));
+        let index = self
+            .skills
+            .iter()
+            .position(|skill| position(&skill.repository) > own)
+            .unwrap_or(self.skills.len());
+        self.skills.splice(index..index, skills);
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.by_id.clear();
+        self.by_repository.clear();
+        for (index, skill) in self.skills.iter().enumerate() {
+            // The first skill with an id wins, as it is listed first in the store
+            self.by_id.entry(skill.id.clone()).or_insert(index);
+            self.by_repository
+                .entry(skill.repository.to_lowercase())
+                .or_default()
+                .push(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SkillCategory;
+
+    fn repository(url: &str) -> Repository {
+        Repository {
+            id: url.replace('/', "-"),
+            url: url.to_string(),
+            name: url.to_string(),
+            is_builtin: false,
+            base_path: None,
+            git_ref: None,
+            last_synced: None,
+            skill_count: None,
+        }
+    }
+
+    fn skill(repository: &str, id: &str, name: &str) -> Skill {
+        Skill {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            repository: repository.to_string(),
+            git_ref: None,
+            path: id.to_string(),
+            category: SkillCategory::Development,
+            readme: None,
+            metadata: None,
+            installed_at: None,
+        }
+    }
+
+    fn names(skills: &[Skill]) -> Vec<&str> {
+        skills.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn replaces_the_previous_skills_of_a_repository_in_place() {
+        let repositories = [
+            repository("a/one"),
+            repository("b/two"),
+            repository("c/three"),
+        ];
+        let mut catalog = SkillCatalog::new(vec![
+            skill("a/one", "a/one/x", "a-x"),
+            skill("b/two", "b/two/old", "b-old"),
+            skill("b/two", "b/two/gone", "b-gone"),
+            skill("c/three", "c/three/z", "c-z"),
+        ]);
+
+        catalog.replace_repository(
+            &repositories,
+            &repositories[1],
+            vec![skill("b/two", "b/two/new", "b-new")],
+        );
+
+        assert_eq!(names(catalog.skills()), ["a-x", "b-new", "c-z"]);
+        assert!(catalog.get("b/two/old").is_none());
+        assert!(catalog.get("b/two/gone").is_none());
+        assert_eq!(catalog.get("b/two/new").unwrap().name, "b-new");
+        assert_eq!(catalog.get("c/three/z").unwrap().name, "c-z");
+        assert_eq!(catalog.repository_skills("B/Two").len(), 1);
+    }
+
+    #[test]
+    fn adds_a_repository_in_configuration_order() {
+        let repositories = [
+            repository("a/one"),
+            repository("b/two"),
+            repository("c/three"),
+        ];
+        let mut catalog = SkillCatalog::new(vec![
+            skill("a/one", "a/one/x", "a-x"),
+            skill("c/three", "c/three/z", "c-z"),
+        ]);
+
+        catalog.replace_repository(
+            &repositories,
+            &repositories[1],
+            vec![skill("b/two", "b/two/y", "b-y")],
+        );
+
+        assert_eq!(names(catalog.skills()), ["a-x", "b-y", "c-z"]);
+    }
+
+    #[test]
+    fn removes_every_skill_of_a_repository_that_has_none_left() {
+        let repositories = [repository("a/one"), repository("b/two")];
+        let mut catalog = SkillCatalog::new(vec![
+            skill("a/one", "a/one/x", "a-x"),
+            skill("B/Two", "b/two/y", "b-y"),
+        ]);
+
+        catalog.replace_repository(&repositories, &repositories[1], Vec::new());
+
+        assert_eq!(names(catalog.skills()), ["a-x"]);
+        assert!(catalog.repository_skills("b/two").is_empty());
+    }
+
+    #[test]
+    fn the_first_skill_with_a_duplicate_id_wins() {
+        let repositories = [repository("a/one"), repository("b/two")];
+        let mut catalog = SkillCatalog::new(vec![
+            skill("a/one", "shared", "from-a"),
+            skill("b/two", "shared", "from-b"),
+        ]);
+        assert_eq!(catalog.get("shared").unwrap().name, "from-a");
+
+        // Replacing the later repository keeps the earlier skill in front
+        catalog.replace_repository(
+            &repositories,
+            &repositories[1],
+            vec![skill("b/two", "shared", "from-b-again")],
+        );
+        assert_eq!(catalog.get("shared").unwrap().name, "from-a");
+
+        // Once the earlier repository drops it, the later one is found
+        catalog.replace_repository(&repositories, &repositories[0], Vec::new());
+        assert_eq!(catalog.get("shared").unwrap().name, "from-b-again");
+        assert_eq!(catalog.repository_skills("b/two").len(), 1);
+    }
+}
@@ -1,7 +1,9 @@
-use crate::models::Repository;
+use crate::models::{Repository, SkillProfile};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,17 +14,134 @@ pub enum ConfigError {
     Parse(#[from] serde_json::Error),
     #[error("Config directory not found")]
     ConfigDirNotFound,
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Invalid profile name: {0}")]
+    InvalidProfileName(String),
 }
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub repositories: Vec<Repository>,
+    /// Number of versions kept per skill for rollback
+    #[serde(default = "default_max_skill_versions")]
+    pub max_skill_versions: usize,
+    /// Days uninstalled skills stay in the trash (0 keeps them forever)
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// Named sets of skills that can be switched between
+    #[serde(default)]
+    pub profiles: Vec<SkillProfile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Repositories (`owner/repo`) with their own token in the OS keychain
+    #[serde(default)]
+    pub token_repositories: Vec<String>,
+    /// How failed GitHub requests are retried; read at launch
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// GitHub requests allowed in flight at once, across all repositories;
+    /// read at launch
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Hours a repository's cached skills are trusted before the background
+    /// refresh checks whether its branch moved (0 checks at every launch)
+    #[serde(default = "default_cache_ttl_hours")]
+    pub cache_ttl_hours: u64,
+    /// Work from caches only, without contacting GitHub
+    #[serde(default)]
+    pub offline: bool,
+    /// Megabytes of downloaded skill files kept for reinstalls
+    #[serde(default = "default_blob_cache_max_mb")]
+    pub blob_cache_max_mb: u64,
+    /// Megabytes of GitHub responses kept for conditional requests; applied
+    /// at launch
+    #[serde(default = "default_http_cache_max_mb")]
+    pub http_cache_max_mb: u64,
+}
+
+impl AppConfig {
+    /// How long cached skills are trusted without checking their branch
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache_ttl_hours * 60 * 60)
+    }
+
+    /// Size limit of the blob cache in bytes
+    pub fn blob_cache_max_bytes(&self) -> u64 {
+        self.blob_cache_max_mb * 1024 * 1024
+    }
+
+    /// Size limit of the HTTP response cache in bytes
+    pub fn http_cache_max_bytes(&self) -> u64 {
+        self.http_cache_max_mb * 1024 * 1024
+    }
+}
+
+/// Retry policy for GitHub requests that fail with a network error, a
+/// timeout or a transient 5xx status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts per request, including the first
+    pub max_attempts: u32,
+    /// Backoff before the first retry; doubled for every further retry
+    pub base_delay_ms: u64,
+    /// Upper bound of a single backoff
+    pub max_delay_ms: u64,
+    /// No retry is started once this many seconds have passed since the first attempt
+    pub deadline_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay_ms: 500,
+            max_delay_ms: 8_000,
+            deadline_secs: 60,
+        }
+    }
+}
+
+fn default_max_skill_versions() -> usize {
+    5
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_max_concurrent_requests() -> usize {
+    8
+}
+
+fn default_cache_ttl_hours() -> u64 {
+    24
+}
+
+fn default_blob_cache_max_mb() -> u64 {
+    256
+}
+
+fn default_http_cache_max_mb() -> u64 {
+    64
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            max_skill_versions: default_max_skill_versions(),
+            trash_retention_days: default_trash_retention_days(),
+            profiles: Vec::new(),
+            active_profile: None,
+            token_repositories: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            max_concurrent_requests: default_max_concurrent_requests(),
+            cache_ttl_hours: default_cache_ttl_hours(),
+            offline: false,
+            blob_cache_max_mb: default_blob_cache_max_mb(),
+            http_cache_max_mb: default_http_cache_max_mb(),
             repositories: vec![Repository {
                 id: "composio-awesome".to_string(),
                 url: "ComposioHQ/awesome-claude-skills".to_string(),
@@ -37,10 +156,13 @@ impl Default for AppConfig {
     }
 }
 
+/// Held across every load-modify-save of the config file
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
 pub struct ConfigService;
 
 impl ConfigService {
-    fn normalize_base_path(base_path: Option<&str>) -> Option<String> {
+    pub(crate) fn normalize_base_path(base_path: Option<&str>) -> Option<String> {
         let base_path = base_path?.trim();
         if base_path.is_empty() {
             return None;
@@ -61,7 +183,7 @@ impl ConfigService {
         Some(base_path.to_string())
     }
 
-    fn normalize_git_ref(git_ref: Option<&str>) -> Option<String> {
+    pub(crate) fn normalize_git_ref(git_ref: Option<&str>) -> Option<String> {
         let git_ref = git_ref?.trim();
         if git_ref.is_empty() {
             return None;
@@ -69,6 +191,12 @@ impl ConfigService {
         Some(git_ref.to_string())
     }
 
+    /// Lock the config file for a load-modify-save, so that changes made
+    /// at the same time (e.g. a background refresh and a command) are not lost
+    pub(crate) fn lock() -> MutexGuard<'static, ()> {
+        CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Get the config file path
     fn get_config_path() -> Result<PathBuf, ConfigError> {
         let home = dirs::home_dir().ok_or(ConfigError::ConfigDirNotFound)?;
@@ -118,6 +246,7 @@ impl ConfigService {
         base_path: Option<&str>,
         git_ref: Option<&str>,
     ) -> Result<Repository, ConfigError> {
+        let _guard = Self::lock();
         let mut config = Self::load()?;
 
         let repo_url = format!("{}/{}", owner, repo);
@@ -169,6 +298,7 @@ impl ConfigService {
 
     /// Remove a custom repository (cannot remove builtin)
     pub fn remove_repository(repo_id: &str) -> Result<bool, ConfigError> {
+        let _guard = Self::lock();
         let mut config = Self::load()?;
 
         // Find the repository
@@ -191,6 +321,7 @@ impl ConfigService {
 
     /// Update repository sync info
     pub fn update_repository_sync(repo_id: &str, skill_count: u32) -> Result<(), ConfigError> {
+        let _guard = Self::lock();
         let mut config = Self::load()?;
 
         if let Some(repo) = config.repositories.iter_mut().find(|r| r.id == repo_id) {
@@ -201,4 +332,135 @@ impl ConfigService {
 
         Ok(())
     }
+
+    /// List all skill profiles
+    pub fn list_profiles() -> Result<Vec<SkillProfile>, ConfigError> {
+        let config = Self::load()?;
+        Ok(config.profiles)
+    }
+
+    /// Create a skill profile
+    pub fn create_profile(name: &str, skills: Vec<String>) -> Result<SkillProfile, ConfigError> {
+        let _guard = Self::lock();
+        let mut config = Self::load()?;
+
+        let name = name.trim();
+        let slug = name
+            .to_lowercase()
+            .replace(' ', "-")
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+            .collect::<String>();
+        if slug.is_empty() {
+            return Err(ConfigError::InvalidProfileName(name.to_string()));
+        }
+
+        // Make the id unique
+        let mut id = slug.clone();
+        let mut suffix = 2;
+        while config.profiles.iter().any(|p| p.id == id) {
+            id = format!("{}-{}", slug, suffix);
+            suffix += 1;
+        }
+
+        let profile = SkillProfile {
+            id,
+            name: name.to_string(),
+            skills: Self::normalize_profile_skills(skills),
+        };
+
+        config.profiles.push(profile.clone());
+        Self::save(&config)?;
+
+        Ok(profile)
+    }
+
+    /// Update the name and/or skills of a profile
+    pub fn update_profile(
+        profile_id: &str,
+        name: Option<&str>,
+        skills: Option<Vec<String>>,
+    ) -> Result<SkillProfile, ConfigError> {
+        let _guard = Self::lock();
+        let mut config = Self::load()?;
+
+        let profile = config
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| ConfigError::ProfileNotFound(profile_id.to_string()))?;
+
+        if let Some(name) = name {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(ConfigError::InvalidProfileName(name.to_string()));
+            }
+            profile.name = name.to_string();
+        }
+
+        if let Some(skills) = skills {
+            profile.skills = Self::normalize_profile_skills(skills);
+        }
+
+        let result = profile.clone();
+        Self::save(&config)?;
+
+        Ok(result)
+    }
+
+    /// Delete a profile
+    pub fn delete_profile(profile_id: &str) -> Result<bool, ConfigError> {
+        let _guard = Self::lock();
+        let mut config = Self::load()?;
+
+        let initial_len = config.profiles.len();
+        config.profiles.retain(|p| p.id != profile_id);
+
+        if config.profiles.len() == initial_len {
+            return Ok(false);
+        }
+
+        if config.active_profile.as_deref() == Some(profile_id) {
+            config.active_profile = None;
+        }
+        Self::save(&config)?;
+
+        Ok(true)
+    }
+
+    /// Get a profile by id
+    pub fn get_profile(profile_id: &str) -> Result<SkillProfile, ConfigError> {
+        Self::load()?
+            .profiles
+            .into_iter()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| ConfigError::ProfileNotFound(profile_id.to_string()))
+    }
+
+    /// Record which profile is active
+    pub fn set_active_profile(profile_id: Option<&str>) -> Result<(), ConfigError> {
+        let _guard = Self::lock();
+        let mut config = Self::load()?;
+        config.active_profile = profile_id.map(|id| id.to_string());
+        Self::save(&config)
+    }
+
+    /// Remember whether the user chose to work offline
+    pub fn set_offline(offline: bool) -> Result<(), ConfigError> {
+        let _guard = Self::lock();
+        let mut config = Self::load()?;
+        config.offline = offline;
+        Self::save(&config)
+    }
+
+    fn normalize_profile_skills(skills: Vec<String>) -> Vec<String> {
+        let mut skills: Vec<String> = skills
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        skills.sort();
+        skills.dedup();
+        skills
+    }
 }
@@ -0,0 +1,301 @@
+use crate::models::TokenSource;
+use crate::services::config::{ConfigError, ConfigService};
+use crate::services::github::{split_repository, GitHubCredentials};
+use keyring::Entry;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Keychain service the tokens are stored under
+const KEYRING_SERVICE: &str = "com.zder.myskills";
+
+/// Keychain account of the default GitHub token
+const DEFAULT_ACCOUNT: &str = "github";
+
+/// Host looked up in the GitHub CLI config and git credential helpers
+const GITHUB_HOST: &str = "github.com";
+
+/// Environment variables checked for a token, in order
+const TOKEN_ENV_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// The GitHub CLI and git are killed when they take longer than this
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Error, Debug)]
+pub enum CredentialError {
+    #[error("Keychain error: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("Invalid repository: {0}")]
+    InvalidRepository(String),
+    #[error("Token is empty")]
+    EmptyToken,
+}
+
+/// GitHub tokens kept in the OS keychain (Keychain, Credential Manager or
+/// Secret Service) rather than in `config.json`
+pub struct CredentialService;
+
+impl CredentialService {
+    /// Normalize an optional `owner/repo` to the lowercase key tokens are stored under
+    fn repository_key(repository: Option<&str>) -> Result<Option<String>, CredentialError> {
+        let Some(repository) = repository.map(|r| r.trim().trim_matches('/')) else {
+            return Ok(None);
+        };
+        if split_repository(repository).is_none() {
+            return Err(CredentialError::InvalidRepository(repository.to_string()));
+        }
+        Ok(Some(repository.to_lowercase()))
+    }
+
+    fn entry(repository: Option<&str>) -> Result<Entry, CredentialError> {
+        let account = match repository {
+            Some(repository) => format!("{}:{}", DEFAULT_ACCOUNT, repository),
+            None => DEFAULT_ACCOUNT.to_string(),
+        };
+        Ok(Entry::new(KEYRING_SERVICE, &account)?)
+    }
+
+    /// Read the default token, or a repository's own token
+    pub fn get_token(repository: Option<&str>) -> Result<Option<String>, CredentialError> {
+        let key = Self::repository_key(repository)?;
+        match Self::entry(key.as_deref())?.get_password() {
+            Ok(token) => Ok(Some(token)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Store the default token, or a token for one repository
+    pub fn set_token(repository: Option<&str>, token: &str) -> Result<(), CredentialError> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(CredentialError::EmptyToken);
+        }
+
+        let key = Self::repository_key(repository)?;
+        Self::entry(key.as_deref())?.set_password(token)?;
+
+        // Keychains cannot be listed, so remember which repositories have a token
+        if let Some(key) = key {
+            let _guard = ConfigService::lock();
+            let mut config = ConfigService::load()?;
+            if !config.token_repositories.contains(&key) {
+                config.token_repositories.push(key);
+                config.token_repositories.sort();
+                ConfigService::save(&config)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete the default token, or a repository's own token
+    pub fn delete_token(repository: Option<&str>) -> Result<bool, CredentialError> {
+        let key = Self::repository_key(repository)?;
+        let deleted = match Self::entry(key.as_deref())?.delete_credential() {
+            Ok(()) => true,
+            Err(keyring::Error::NoEntry) => false,
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(key) = key {
+            let _guard = ConfigService::lock();
+            let mut config = ConfigService::load()?;
+            let before = config.token_repositories.len();
+            config.token_repositories.retain(|r| r != &key);
+            if config.token_repositories.len() != before {
+                ConfigService::save(&config)?;
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Load every stored token. Without a token in the keychain, the default
+    /// token is taken from the environment; see `discover_token` for the rest.
+    pub fn load() -> Result<GitHubCredentials, CredentialError> {
+        let (token, source) = match Self::get_token(None)? {
+            Some(token) => (Some(token), Some(TokenSource::Keychain)),
+            None => match token_from_env() {
+                Some(token) => (Some(token), Some(TokenSource::Env)),
+                None => (None, None),
+            },
+        };
+
+        let mut credentials = GitHubCredentials {
+            token,
+            source,
+            ..Default::default()
+        };
+
+        for repository in ConfigService::load()?.token_repositories {
+            if let Some(token) = Self::get_token(Some(&repository))? {
+                credentials.repository_tokens.insert(repository, token);
+            }
+        }
+
+        Ok(credentials)
+    }
+
+    /// Look for a token of the GitHub CLI or a git credential helper. This
+    /// runs external programs, so it blocks for up to a few seconds.
+    pub fn discover_token() -> Option<(String, TokenSource)> {
+        if let Some(token) = token_from_gh() {
+            return Some((token, TokenSource::Gh));
+        }
+        if let Some(token) = token_from_git() {
+            return Some((token, TokenSource::Git));
+        }
+        None
+    }
+}
+
+fn non_empty(token: &str) -> Option<String> {
+    let token = token.trim().trim_matches(|c| c == '"' || c == '\'');
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// `GH_TOKEN` or `GITHUB_TOKEN`
+fn token_from_env() -> Option<String> {
+    TOKEN_ENV_VARS
+        .iter()
+        .find_map(|name| std::env::var(name).ok().and_then(|v| non_empty(&v)))
+}
+
+/// Config directory of the GitHub CLI, following its own lookup order
+fn gh_config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GH_CONFIG_DIR").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("gh"));
+    }
+    if cfg!(windows) {
+        return dirs::config_dir().map(|dir| dir.join("GitHub CLI"));
+    }
+    dirs::home_dir().map(|home| home.join(".config").join("gh"))
+}
+
+/// Token of a `gh auth login`. Older versions of the CLI keep it in
+/// `hosts.yml`; newer ones keep it in the keychain and hand it out through
+/// `gh auth token`.
+fn token_from_gh() -> Option<String> {
+    let from_hosts = gh_config_dir()
+        .and_then(|dir| fs::read_to_string(dir.join("hosts.yml")).ok())
+        .and_then(|content| parse_gh_hosts(&content, GITHUB_HOST));
+    if from_hosts.is_some() {
+        return from_hosts;
+    }
+
+    let output = run_quietly(
+        Command::new("gh").args(["auth", "token", "--hostname", GITHUB_HOST]),
+        None,
+    )?;
+    if !output.status.success() {
+        return None;
+    }
+    non_empty(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Read `oauth_token` of a host from the GitHub CLI `hosts.yml`:
+///
+/// ```yaml
+/// github.com:
+///     user: octocat
+///     oauth_token: gho_xxx
+/// ```
+fn parse_gh_hosts(content: &str, host: &str) -> Option<String> {
+    let mut in_host = false;
+    let mut host_indent = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            in_host = line.trim_end().trim_end_matches(':').trim_matches('"') == host;
+            host_indent = None;
+            continue;
+        }
+        if !in_host {
+            continue;
+        }
+
+        // Only keys directly under the host, not those of nested `users:`
+        let host_indent = *host_indent.get_or_insert(indent);
+        if indent != host_indent {
+            continue;
+        }
+        if let Some(value) = line.trim().strip_prefix("oauth_token:") {
+            return non_empty(value);
+        }
+    }
+
+    None
+}
+
+/// Password a git credential helper returns for github.com
+fn token_from_git() -> Option<String> {
+    let input = format!("protocol=https\nhost={}\n\n", GITHUB_HOST);
+    let output = run_quietly(
+        Command::new("git").args(["credential", "fill"]),
+        Some(input.as_bytes()),
+    )?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .and_then(non_empty)
+}
+
+/// Run a command with interactive prompts disabled, so a missing credential
+/// never waits for input, and kill it after `DISCOVERY_TIMEOUT`
+fn run_quietly(command: &mut Command, input: Option<&[u8]>) -> Option<Output> {
+    let mut child = command
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "never")
+        .env_remove("GIT_ASKPASS")
+        .env_remove("SSH_ASKPASS")
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    if let Some(input) = input {
+        // Dropping stdin closes it, which ends the input
+        let written = child.stdin.take()?.write_all(input);
+        if written.is_err() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+    }
+
+    let deadline = Instant::now() + DISCOVERY_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => return child.wait_with_output().ok(),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(25)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
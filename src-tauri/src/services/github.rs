@@ -1,10 +1,27 @@
-use crate::models::{GitHubContent, Skill, SkillCategory, SkillMetadata};
+use crate::models::{
+    GitHubCommit, GitHubContent, GitHubRateLimit, GitHubTree, GitHubUser, OfflineReason,
+    ProgressDetail, ProgressStage, RateLimitInfo, Skill, SkillCategory, SkillMetadata, TokenSource,
+    TokenValidation,
+};
+use crate::services::cache::{CacheService, HttpCacheEntry};
+use crate::services::config::RetryPolicy;
+use crate::services::operations::Operation;
 use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::try_join_all;
+use rand::Rng;
 use regex::Regex;
-use reqwest::{Client, Proxy};
-use std::collections::{HashSet, VecDeque};
-use std::time::Duration;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{Client, Proxy, RequestBuilder, Response, StatusCode};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 #[derive(Error, Debug)]
 pub enum GitHubError {
@@ -12,14 +29,85 @@ pub enum GitHubError {
     Network(#[from] reqwest::Error),
     #[error("Parse error: {0}")]
     Parse(String),
-    #[error("Rate limited")]
-    RateLimited,
+    #[error(
+        "GitHub API rate limit exceeded, resets at {}",
+        .reset_at.format("%Y-%m-%d %H:%M:%S UTC")
+    )]
+    RateLimited {
+        reset_at: chrono::DateTime<chrono::Utc>,
+    },
+    #[error("GitHub secondary rate limit hit, retry in {retry_after} seconds")]
+    SecondaryRateLimited { retry_after: u64 },
+    #[error("Access denied: {0}")]
+    Forbidden(String),
+    #[error("Request to {url} failed after {attempts} attempts: {message}")]
+    RetriesExhausted {
+        url: String,
+        attempts: u32,
+        /// Status of the last response, if the server answered at all
+        last_status: Option<u16>,
+        message: String,
+    },
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Offline: GitHub is not contacted in offline mode")]
+    Offline,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl GitHubError {
+    /// How long to wait before a rate-limited request can succeed again
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            GitHubError::RateLimited { reset_at } => {
+                let wait = (*reset_at - chrono::Utc::now())
+                    .num_seconds()
+                    .clamp(0, 3600);
+                Some(Duration::from_secs(wait as u64 + 1))
+            }
+            GitHubError::SecondaryRateLimited { retry_after } => {
+                Some(Duration::from_secs(*retry_after))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Requests in flight at once until the configured limit is applied
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Downloaded bytes between two progress events of a tarball download
+const PROGRESS_BYTES_STEP: u64 = 256 * 1024;
+
+/// Requests in a row that fail to reach GitHub before offline mode is entered
+const OFFLINE_AFTER_FAILURES: u32 = 3;
+
+/// Media type of GitHub REST API responses
+const GITHUB_API_ACCEPT: &str = "application/vnd.github.v3+json";
+
+/// GitHub tokens used to authenticate requests
+#[derive(Debug, Clone, Default)]
+pub struct GitHubCredentials {
+    pub token: Option<String>,
+    /// Where `token` was found
+    pub source: Option<TokenSource>,
+    /// Tokens for specific repositories, keyed by lowercase `owner/repo`
+    pub repository_tokens: HashMap<String, String>,
 }
 
 pub struct GitHubService {
     client: Client,
+    credentials: RwLock<GitHubCredentials>,
+    /// Core API quota as last reported by GitHub
+    rate_limit: RwLock<Option<RateLimitInfo>>,
+    retry_policy: RwLock<RetryPolicy>,
+    /// Permits for requests in flight, shared by every caller of the service
+    request_slots: RwLock<Arc<Semaphore>>,
+    /// Why the network is not used, while in offline mode
+    offline: RwLock<Option<OfflineReason>>,
+    /// Requests in a row that could not reach GitHub at all
+    connection_failures: AtomicU32,
 }
 
 impl GitHubService {
@@ -44,7 +132,174 @@ impl GitHubService {
 
         let client = builder.build().unwrap_or_else(|_| Client::new());
 
-        Self { client }
+        Self {
+            client,
+            credentials: RwLock::new(GitHubCredentials::default()),
+            rate_limit: RwLock::new(None),
+            retry_policy: RwLock::new(RetryPolicy::default()),
+            request_slots: RwLock::new(Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_REQUESTS))),
+            offline: RwLock::new(None),
+            connection_failures: AtomicU32::new(0),
+        }
+    }
+
+    /// Replace the tokens used for subsequent requests
+    pub fn set_credentials(&self, credentials: GitHubCredentials) {
+        if let Ok(mut current) = self.credentials.write() {
+            *current = credentials;
+        }
+        // The quota seen so far belonged to the previous token
+        if let Ok(mut rate_limit) = self.rate_limit.write() {
+            *rate_limit = None;
+        }
+    }
+
+    /// Use a token found outside the app, unless a default token was set
+    /// while it was being looked for
+    pub fn set_discovered_token(&self, token: String, source: TokenSource) {
+        if let Ok(mut current) = self.credentials.write() {
+            if current.token.is_none() {
+                current.token = Some(token);
+                current.source = Some(source);
+            }
+        }
+    }
+
+    /// Get the tokens currently in use
+    pub fn credentials(&self) -> GitHubCredentials {
+        self.credentials
+            .read()
+            .map(|c| c.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the policy used to retry failed requests
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        if let Ok(mut current) = self.retry_policy.write() {
+            *current = policy;
+        }
+    }
+
+    /// Limit how many requests may be in flight at once. Requests already
+    /// waiting keep the previous limit.
+    pub fn set_max_concurrent_requests(&self, max: usize) {
+        if let Ok(mut slots) = self.request_slots.write() {
+            *slots = Arc::new(Semaphore::new(max.max(1)));
+        }
+    }
+
+    fn request_slots(&self) -> Arc<Semaphore> {
+        self.request_slots
+            .read()
+            .map(|s| s.clone())
+            .unwrap_or_else(|_| Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_REQUESTS)))
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+            .read()
+            .map(|p| p.clone())
+            .unwrap_or_default()
+    }
+
+    /// Enter offline mode for `reason`, or leave it with `None`. Requests
+    /// are then answered from the response cache or fail with `Offline`.
+    pub fn set_offline(&self, reason: Option<OfflineReason>) {
+        if let Ok(mut offline) = self.offline.write() {
+            *offline = reason;
+        }
+        self.connection_failures.store(0, Ordering::SeqCst);
+    }
+
+    /// Why the service is offline, if it is
+    pub fn offline_reason(&self) -> Option<OfflineReason> {
+        self.offline.read().ok().and_then(|o| *o)
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline_reason().is_some()
+    }
+
+    fn ensure_online(&self) -> Result<(), GitHubError> {
+        match self.is_offline() {
+            true => Err(GitHubError::Offline),
+            false => Ok(()),
+        }
+    }
+
+    /// Count a request that never reached GitHub; enough of them in a row
+    /// switch to offline mode so later requests fail fast
+    fn record_connection_failure(&self) {
+        let failures = self.connection_failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures >= OFFLINE_AFTER_FAILURES && !self.is_offline() {
+            println!(
+                "[Rust] {} requests in a row could not reach GitHub, going offline",
+                failures
+            );
+            self.set_offline(Some(OfflineReason::ConnectionFailures));
+        }
+    }
+
+    /// Core API quota as of the last API response, if any was seen yet
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit.read().ok().and_then(|r| r.clone())
+    }
+
+    /// Ask GitHub for the current core API quota of the default token
+    pub async fn refresh_rate_limit(&self) -> Result<RateLimitInfo, GitHubError> {
+        let token = self.credentials().token;
+        let rate_limit = self.fetch_rate_limit(token.as_deref()).await?;
+        if let Ok(mut current) = self.rate_limit.write() {
+            *current = Some(rate_limit.clone());
+        }
+        Ok(rate_limit)
+    }
+
+    /// Remember the quota reported in the headers of an API response
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(rate_limit) = rate_limit_from_headers(headers) {
+            if let Ok(mut current) = self.rate_limit.write() {
+                *current = Some(rate_limit);
+            }
+        }
+    }
+
+    /// Pick the token for a request: the repository's own token if one is
+    /// configured, otherwise the default token. Only GitHub hosts get a token.
+    fn token_for_url(&self, url: &str) -> Option<String> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let segments: Vec<&str> = url.path_segments()?.collect();
+
+        let repository = match url.host_str()? {
+            "api.github.com" => match segments.as_slice() {
+                ["repos", owner, repo, ..] => Some(format!("{}/{}", owner, repo)),
+                _ => None,
+            },
+            "raw.githubusercontent.com" | "codeload.github.com" => match segments.as_slice() {
+                [owner, repo, ..] => Some(format!("{}/{}", owner, repo)),
+                _ => None,
+            },
+            _ => return None,
+        };
+
+        let credentials = self.credentials.read().ok()?;
+        repository
+            .and_then(|r| {
+                credentials
+                    .repository_tokens
+                    .get(&r.to_lowercase())
+                    .cloned()
+            })
+            .or_else(|| credentials.token.clone())
+    }
+
+    /// Build a GET request, authenticated when a token is given
+    fn get(&self, url: &str, token: Option<&str>) -> RequestBuilder {
+        let request = self.client.get(url).header("User-Agent", "MySkills-App");
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
     }
 
     /// Get proxy URL from environment variables
@@ -59,83 +314,266 @@ impl GitHubService {
             .or_else(|| std::env::var("all_proxy").ok())
     }
 
-    /// Make HTTP request with retry logic
-    async fn request_with_retry(
+    /// Make an API request with retry logic
+    async fn request_with_retry(&self, url: &str) -> Result<SlotResponse, GitHubError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(GITHUB_API_ACCEPT));
+        self.request_with_headers(url, headers).await
+    }
+
+    /// Make HTTP request with extra headers, retrying transport errors,
+    /// timeouts and transient 5xx statuses with full-jitter exponential
+    /// backoff until the retry policy runs out of attempts or time
+    async fn request_with_headers(
         &self,
         url: &str,
-        max_retries: u32,
-    ) -> Result<reqwest::Response, GitHubError> {
-        let mut last_error = None;
-
-        for attempt in 0..max_retries {
-            if attempt > 0 {
-                // Wait before retry (exponential backoff)
-                tokio::time::sleep(Duration::from_millis(500 * (1 << attempt))).await;
-            }
-
-            match self
-                .client
-                .get(url)
-                .header("User-Agent", "MySkills-App")
-                .header("Accept", "application/vnd.github.v3+json")
+        headers: HeaderMap,
+    ) -> Result<SlotResponse, GitHubError> {
+        let policy = self.retry_policy();
+        let deadline = Instant::now() + Duration::from_secs(policy.deadline_secs);
+        let token = self.token_for_url(url);
+        let slots = self.request_slots();
+        let mut attempts = 0;
+
+        loop {
+            self.ensure_online()?;
+            attempts += 1;
+
+            // The slot is held until the body is read, but not while backing off
+            let permit = slots.clone().acquire_owned().await.ok();
+            let result = self
+                .get(url, token.as_deref())
+                .headers(headers.clone())
                 .send()
-                .await
-            {
-                Ok(response) => return Ok(response),
-                Err(e) => {
-                    last_error = Some(e);
-                    continue;
+                .await;
+
+            let (last_status, message) = match result {
+                Ok(response) => {
+                    self.connection_failures.store(0, Ordering::SeqCst);
+                    self.record_rate_limit(response.headers());
+                    if !is_transient(response.status()) {
+                        return Ok(SlotResponse {
+                            response,
+                            _permit: permit,
+                        });
+                    }
+                    (
+                        Some(response.status().as_u16()),
+                        format!("HTTP {}", response.status()),
+                    )
                 }
-            }
+                Err(e) => (e.status().map(|s| s.as_u16()), e.to_string()),
+            };
+
+            let retry = retry_delay(
+                &policy,
+                attempts,
+                Instant::now(),
+                deadline,
+                &mut rand::thread_rng(),
+            );
+            let Some(delay) = retry else {
+                if last_status.is_none() {
+                    self.record_connection_failure();
+                }
+                return Err(GitHubError::RetriesExhausted {
+                    url: url.to_string(),
+                    attempts,
+                    last_status,
+                    message,
+                });
+            };
+
+            println!(
+                "[Rust] {} for {} (attempt {}/{}), retrying in {}ms",
+                message,
+                url,
+                attempts,
+                policy.max_attempts.max(1),
+                delay.as_millis()
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn request_bytes_with_retry(&self, url: &str) -> Result<Vec<u8>, GitHubError> {
+        let response = self.request_with_headers(url, HeaderMap::new()).await?;
+
+        if is_forbidden(response.status()) {
+            return Err(response.forbidden_error(url).await);
+        }
+        if response.status() == 404 {
+            return Err(GitHubError::NotFound(url.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(GitHubError::Parse(format!(
+                "Unexpected status {} for {}",
+                response.status(),
+                url
+            )));
         }
 
-        Err(GitHubError::Network(last_error.unwrap()))
+        Ok(response.bytes().await?)
     }
 
-    async fn request_bytes_with_retry(
-        &self,
-        url: &str,
-        max_retries: u32,
-    ) -> Result<Vec<u8>, GitHubError> {
-        let mut last_error = None;
+    /// Make a conditional GET request. The ETag and Last-Modified values of
+    /// the previous response are sent along, and a 304 is answered from the
+    /// body stored with them; GitHub does not count 304s against the rate
+    /// limit. Returns `None` when there is nothing at `url` (404, or 409 and
+    /// 422 for an empty repository or unknown ref).
+    async fn request_cached(&self, url: &str) -> Result<Option<String>, GitHubError> {
+        let cached = CacheService::load_http_entry(url).ok().flatten();
+
+        // Offline, whatever was stored last is served without revalidation
+        if self.is_offline() {
+            return cached
+                .map(|entry| Some(entry.body))
+                .ok_or(GitHubError::Offline);
+        }
 
-        for attempt in 0..max_retries {
-            if attempt > 0 {
-                tokio::time::sleep(Duration::from_millis(500 * (1 << attempt))).await;
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(GITHUB_API_ACCEPT));
+        if let Some(entry) = &cached {
+            if let Some(etag) = entry.etag.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(IF_NONE_MATCH, etag);
+            }
+            if let Some(date) = entry.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(IF_MODIFIED_SINCE, date);
             }
+        }
 
-            match self
-                .client
-                .get(url)
-                .header("User-Agent", "MySkills-App")
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status() == 403 {
-                        return Err(GitHubError::RateLimited);
-                    }
-                    if response.status() == 404 {
-                        return Err(GitHubError::NotFound(url.to_string()));
-                    }
-                    if !response.status().is_success() {
-                        return Err(GitHubError::Parse(format!(
-                            "Unexpected status {} for {}",
-                            response.status(),
-                            url
-                        )));
-                    }
-                    let bytes = response.bytes().await?;
-                    return Ok(bytes.to_vec());
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                    continue;
-                }
+        let response = self.request_with_headers(url, headers).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                return Ok(Some(entry.body));
+            }
+        }
+
+        if is_forbidden(response.status()) {
+            return Err(response.forbidden_error(url).await);
+        }
+
+        if matches!(
+            response.status(),
+            StatusCode::NOT_FOUND | StatusCode::CONFLICT | StatusCode::UNPROCESSABLE_ENTITY
+        ) {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(GitHubError::Parse(format!(
+                "Unexpected status {} for {}",
+                response.status(),
+                url
+            )));
+        }
+
+        let etag = header_value::<String>(response.headers(), ETAG.as_str());
+        let last_modified = header_value::<String>(response.headers(), LAST_MODIFIED.as_str());
+        let body = response.text().await?;
+
+        if etag.is_some() || last_modified.is_some() {
+            let entry = HttpCacheEntry {
+                url: url.to_string(),
+                etag,
+                last_modified,
+                body: body.clone(),
+            };
+            if let Err(e) = CacheService::save_http_entry(&entry) {
+                eprintln!("[Rust] Failed to cache response for {}: {}", url, e);
             }
         }
 
-        Err(GitHubError::Network(last_error.unwrap()))
+        Ok(Some(body))
+    }
+
+    /// Check a token: who it belongs to, its OAuth scopes and remaining quota
+    pub async fn validate_token(&self, token: &str) -> Result<TokenValidation, GitHubError> {
+        self.ensure_online()?;
+        let response = self
+            .get("https://api.github.com/user", Some(token))
+            .header("Accept", GITHUB_API_ACCEPT)
+            .send()
+            .await?;
+
+        if response.status() == 401 {
+            return Ok(TokenValidation {
+                valid: false,
+                login: None,
+                scopes: Vec::new(),
+                rate_limit: None,
+                error: Some("Bad credentials".to_string()),
+            });
+        }
+
+        if is_forbidden(response.status()) {
+            return Err(forbidden_error(response, "/user").await);
+        }
+
+        if !response.status().is_success() {
+            return Err(GitHubError::Parse(format!(
+                "Unexpected status {} for /user",
+                response.status()
+            )));
+        }
+
+        // Fine-grained tokens have no OAuth scopes and report none
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| {
+                v.split(',')
+                    .map(|scope| scope.trim().to_string())
+                    .filter(|scope| !scope.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let user: GitHubUser = response.json().await?;
+        let rate_limit = self.fetch_rate_limit(Some(token)).await.ok();
+
+        Ok(TokenValidation {
+            valid: true,
+            login: Some(user.login),
+            scopes,
+            rate_limit,
+            error: None,
+        })
+    }
+
+    /// Get the core API quota for a token (anonymous when `None`).
+    /// This request does not count against the quota itself.
+    pub async fn fetch_rate_limit(
+        &self,
+        token: Option<&str>,
+    ) -> Result<RateLimitInfo, GitHubError> {
+        self.ensure_online()?;
+        let response = self
+            .get("https://api.github.com/rate_limit", token)
+            .header("Accept", GITHUB_API_ACCEPT)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::Parse(format!(
+                "Unexpected status {} for /rate_limit",
+                response.status()
+            )));
+        }
+
+        let rate_limit: GitHubRateLimit = response.json().await?;
+        let core = rate_limit.resources.core;
+
+        Ok(RateLimitInfo {
+            limit: core.limit,
+            remaining: core.remaining,
+            used: core.used,
+            reset_at: chrono::DateTime::from_timestamp(core.reset, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+        })
     }
 
     /// Fetch repository contents from GitHub API
@@ -156,18 +594,116 @@ impl GitHubService {
             url.query_pairs_mut().append_pair("ref", git_ref);
         }
 
-        let response = self.request_with_retry(url.as_str(), 3).await?;
+        let body = self
+            .request_cached(url.as_str())
+            .await?
+            .ok_or_else(|| GitHubError::NotFound(path.to_string()))?;
 
-        if response.status() == 403 {
-            return Err(GitHubError::RateLimited);
-        }
+        serde_json::from_str(&body).map_err(|e| GitHubError::Parse(e.to_string()))
+    }
 
-        if response.status() == 404 {
-            return Err(GitHubError::NotFound(path.to_string()));
+    /// Fetch a commit for a branch, tag or commit (default branch when `None`)
+    pub async fn fetch_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: Option<&str>,
+    ) -> Result<GitHubCommit, GitHubError> {
+        let git_ref = git_ref.unwrap_or("HEAD");
+        let url = reqwest::Url::parse(&format!(
+            "https://api.github.com/repos/{}/{}/commits/{}",
+            owner, repo, git_ref
+        ))
+        .map_err(|e| GitHubError::Parse(e.to_string()))?;
+
+        // Revalidated, so checking whether a branch moved is free while it has not
+        let body = self
+            .request_cached(url.as_str())
+            .await?
+            .ok_or_else(|| GitHubError::NotFound(format!("{}/{}@{}", owner, repo, git_ref)))?;
+
+        serde_json::from_str(&body).map_err(|e| GitHubError::Parse(e.to_string()))
+    }
+
+    /// Resolve a branch, tag or commit (default branch when `None`) to a commit SHA
+    pub async fn resolve_commit_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: Option<&str>,
+    ) -> Result<String, GitHubError> {
+        Ok(self.fetch_commit(owner, repo, git_ref).await?.sha)
+    }
+
+    /// Get the git tree SHA of a directory; it changes whenever anything below it changes
+    pub async fn fetch_tree_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<String, GitHubError> {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            let commit = self.fetch_commit(owner, repo, git_ref).await?;
+            return Ok(commit.commit.tree.sha);
         }
 
-        let contents: Vec<GitHubContent> = response.json().await?;
-        Ok(contents)
+        // The contents listing of the parent reports a `sha` for each entry
+        let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+        let contents = self.fetch_contents(owner, repo, parent, git_ref).await?;
+
+        contents
+            .into_iter()
+            .find(|item| item.content_type == "dir" && item.name == name)
+            .and_then(|item| item.sha)
+            .ok_or_else(|| GitHubError::NotFound(path.to_string()))
+    }
+
+    /// Fetch the whole git tree of a branch, tag or commit in one request
+    pub async fn fetch_tree(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: Option<&str>,
+    ) -> Result<GitHubTree, GitHubError> {
+        let git_ref = git_ref.unwrap_or("HEAD");
+        let mut url = reqwest::Url::parse(&format!(
+            "https://api.github.com/repos/{}/{}/git/trees/{}",
+            owner, repo, git_ref
+        ))
+        .map_err(|e| GitHubError::Parse(e.to_string()))?;
+        url.query_pairs_mut().append_pair("recursive", "1");
+
+        let body = self
+            .request_cached(url.as_str())
+            .await?
+            .ok_or_else(|| GitHubError::NotFound(format!("{}/{}@{}", owner, repo, git_ref)))?;
+
+        serde_json::from_str(&body).map_err(|e| GitHubError::Parse(e.to_string()))
+    }
+
+    /// Download a file from raw.githubusercontent.com, which does not count
+    /// against the API rate limit
+    async fn fetch_raw_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<String, GitHubError> {
+        let mut url = reqwest::Url::parse("https://raw.githubusercontent.com/")
+            .map_err(|e| GitHubError::Parse(e.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| GitHubError::Parse("Invalid raw URL".to_string()))?
+            .push(owner)
+            .push(repo)
+            .push(git_ref.unwrap_or("HEAD"))
+            .extend(path.split('/'));
+
+        self.request_cached(url.as_str())
+            .await?
+            .ok_or_else(|| GitHubError::NotFound(url.to_string()))
     }
 
     /// Fetch file content from GitHub
@@ -188,13 +724,12 @@ impl GitHubService {
             url.query_pairs_mut().append_pair("ref", git_ref);
         }
 
-        let response = self.request_with_retry(url.as_str(), 3).await?;
-
-        if response.status() == 404 {
-            return Err(GitHubError::NotFound(path.to_string()));
-        }
-
-        let content: GitHubContent = response.json().await?;
+        let body = self
+            .request_cached(url.as_str())
+            .await?
+            .ok_or_else(|| GitHubError::NotFound(path.to_string()))?;
+        let content: GitHubContent =
+            serde_json::from_str(&body).map_err(|e| GitHubError::Parse(e.to_string()))?;
 
         if let Some(encoded) = content.content {
             let decoded = STANDARD
@@ -224,7 +759,11 @@ impl GitHubService {
             url.query_pairs_mut().append_pair("ref", git_ref);
         }
 
-        let response = self.request_with_retry(url.as_str(), 3).await?;
+        let response = self.request_with_retry(url.as_str()).await?;
+
+        if is_forbidden(response.status()) {
+            return Err(response.forbidden_error(url.as_str()).await);
+        }
 
         if response.status() == 404 {
             return Err(GitHubError::NotFound(path.to_string()));
@@ -237,18 +776,90 @@ impl GitHubService {
                 .decode(encoded.replace('\n', ""))
                 .map_err(|e| GitHubError::Parse(e.to_string()))
         } else if let Some(download_url) = content.download_url {
-            self.request_bytes_with_retry(&download_url, 3).await
+            self.request_bytes_with_retry(&download_url).await
         } else {
             Err(GitHubError::Parse("No content found".to_string()))
         }
     }
 
+    /// Download the gzipped tarball of a repository at a branch, tag or
+    /// commit into `dest`, writing it to disk chunk by chunk
+    pub async fn download_tarball(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: &str,
+        dest: &Path,
+        progress: Option<&Operation>,
+    ) -> Result<(), GitHubError> {
+        let url = reqwest::Url::parse(&format!(
+            "https://api.github.com/repos/{}/{}/tarball/{}",
+            owner, repo, git_ref
+        ))
+        .map_err(|e| GitHubError::Parse(e.to_string()))?;
+
+        let mut response = self.request_with_retry(url.as_str()).await?;
+
+        if is_forbidden(response.status()) {
+            return Err(response.forbidden_error(url.as_str()).await);
+        }
+
+        if response.status() == 404 {
+            return Err(GitHubError::NotFound(format!(
+                "{}/{}@{}",
+                owner, repo, git_ref
+            )));
+        }
+
+        if !response.status().is_success() {
+            return Err(GitHubError::Parse(format!(
+                "Unexpected status {} for {}",
+                response.status(),
+                url
+            )));
+        }
+
+        let total_bytes = response.content_length();
+        let report = |bytes: u64| {
+            if let Some(progress) = progress {
+                progress.emit(
+                    ProgressStage::Progress,
+                    ProgressDetail {
+                        repository: Some(format!("{}/{}", owner, repo)),
+                        bytes: Some(bytes),
+                        total_bytes,
+                        ..Default::default()
+                    },
+                );
+            }
+        };
+
+        let mut file = tokio::fs::File::create(dest).await?;
+        let mut bytes = 0u64;
+        let mut reported = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            bytes += chunk.len() as u64;
+            if bytes - reported >= PROGRESS_BYTES_STEP {
+                report(bytes);
+                reported = bytes;
+            }
+        }
+        file.flush().await?;
+        report(bytes);
+
+        Ok(())
+    }
+
+    /// Get every file below a directory. Files are read from the blob cache
+    /// when they are in it, and downloaded and added to it otherwise.
     pub async fn download_directory_files(
         &self,
         owner: &str,
         repo: &str,
         dir_path: &str,
         git_ref: Option<&str>,
+        progress: Option<&Operation>,
     ) -> Result<Vec<(String, Vec<u8>)>, GitHubError> {
         let base_dir = dir_path.trim_matches('/').to_string();
         let mut files = Vec::new();
@@ -261,12 +872,26 @@ impl GitHubService {
 
             for item in contents {
                 match item.content_type.as_str() {
-                    "file" => {
-                        let bytes = if let Some(download_url) = item.download_url {
-                            self.request_bytes_with_retry(&download_url, 3).await?
-                        } else {
-                            self.fetch_file_bytes(owner, repo, &item.path, git_ref)
-                                .await?
+                    // Symlinks are downloaded as a file holding the link target
+                    "file" | "symlink" => {
+                        let cached = item
+                            .sha
+                            .as_deref()
+                            .and_then(|sha| CacheService::load_blob(sha).ok().flatten());
+                        let bytes = match cached {
+                            Some(bytes) => bytes,
+                            None => {
+                                let bytes = if let Some(download_url) = item.download_url {
+                                    self.request_bytes_with_retry(&download_url).await?
+                                } else {
+                                    self.fetch_file_bytes(owner, repo, &item.path, git_ref)
+                                        .await?
+                                };
+                                if let Err(e) = CacheService::save_blob(&bytes) {
+                                    eprintln!("[Rust] Failed to cache {}: {}", item.path, e);
+                                }
+                                bytes
+                            }
                         };
 
                         let relative = item
@@ -275,6 +900,17 @@ impl GitHubService {
                             .unwrap_or(item.path.as_str())
                             .trim_start_matches('/')
                             .to_string();
+                        if let Some(progress) = progress {
+                            progress.emit(
+                                ProgressStage::Progress,
+                                ProgressDetail {
+                                    file: Some(relative.clone()),
+                                    bytes: Some(bytes.len() as u64),
+                                    done: Some(files.len() as u32 + 1),
+                                    ..Default::default()
+                                },
+                            );
+                        }
                         files.push((relative, bytes));
                     }
                     "dir" => {
@@ -289,21 +925,62 @@ impl GitHubService {
     }
 
     /// Scan repository for skills
+    ///
+    /// The whole tree is fetched in a single request and only the SKILL.md
+    /// files are downloaded. Trees too large for GitHub to return completely
+    /// are walked directory by directory instead.
     pub async fn scan_skills(
         &self,
         owner: &str,
         repo: &str,
         base_path: Option<&str>,
         git_ref: Option<&str>,
+    ) -> Result<Vec<Skill>, GitHubError> {
+        let base_path = base_path.unwrap_or("").trim_matches('/');
+
+        let tree = self.fetch_tree(owner, repo, git_ref).await?;
+        let Some(skill_md_paths) = find_skill_md_paths(&tree, base_path) else {
+            println!(
+                "[Rust] tree of {}/{} is truncated, scanning directories instead",
+                owner, repo
+            );
+            return self.walk_skills(owner, repo, base_path, git_ref).await;
+        };
+        // A missing base path is an error rather than a repository without skills
+        if !contains_path(&tree, base_path) {
+            return Err(GitHubError::NotFound(format!(
+                "{}/{}/{}",
+                owner, repo, base_path
+            )));
+        }
+
+        // SKILL.md files are fetched concurrently; results keep tree order
+        try_join_all(
+            skill_md_paths
+                .iter()
+                .map(|(dir_path, skill_md_path)| async move {
+                    let content = self
+                        .fetch_raw_file(owner, repo, skill_md_path, git_ref)
+                        .await?;
+                    Ok(build_skill(owner, repo, dir_path, git_ref, content))
+                }),
+        )
+        .await
+    }
+
+    /// Scan repository for skills with one contents request per directory
+    async fn walk_skills(
+        &self,
+        owner: &str,
+        repo: &str,
+        base_path: &str,
+        git_ref: Option<&str>,
     ) -> Result<Vec<Skill>, GitHubError> {
         let mut skills = Vec::new();
 
-        let base_path = base_path.unwrap_or("").trim_matches('/').to_string();
-        let mut queue = VecDeque::from([(base_path, 0u32)]);
+        let mut queue = VecDeque::from([(base_path.to_string(), 0u32)]);
         let mut visited = HashSet::<String>::new();
 
-        const MAX_DEPTH: u32 = 3;
-
         while let Some((dir_path, depth)) = queue.pop_front() {
             if !visited.insert(dir_path.clone()) {
                 continue;
@@ -323,7 +1000,7 @@ impl GitHubService {
                 continue;
             }
 
-            if depth >= MAX_DEPTH {
+            if depth >= MAX_SCAN_DEPTH {
                 continue;
             }
 
@@ -362,46 +1039,165 @@ impl GitHubService {
             .fetch_file(owner, repo, &skill_md_path, git_ref)
             .await?;
 
-        // Parse frontmatter and content
-        let (metadata, description) = parse_skill_md(&content);
+        Ok(build_skill(owner, repo, dir_path, git_ref, content))
+    }
+}
 
-        let folder_name = if dir_path.is_empty() {
-            repo.to_string()
-        } else {
-            dir_path.rsplit('/').next().unwrap_or("skill").to_string()
-        };
+/// Skills are looked for at most this many directories below the base path
+const MAX_SCAN_DEPTH: u32 = 3;
+
+/// Find the skill directories in a recursive tree, using the same rules as
+/// the directory walk: at most `MAX_SCAN_DEPTH` levels below `base_path`, and
+/// nothing below a directory that is already a skill.
+///
+/// Returns `(skill directory, SKILL.md path)` pairs, shallowest first, or
+/// `None` when the tree is truncated and has to be walked instead.
+fn find_skill_md_paths(tree: &GitHubTree, base_path: &str) -> Option<Vec<(String, String)>> {
+    if tree.truncated {
+        return None;
+    }
 
-        let name = metadata
-            .name
-            .clone()
-            .unwrap_or_else(|| folder_name.replace('-', " "));
+    let mut found: Vec<(String, String)> = tree
+        .tree
+        .iter()
+        .filter(|entry| entry.entry_type == "blob")
+        .filter_map(|entry| {
+            let (dir_path, file_name) = match entry.path.rsplit_once('/') {
+                Some((dir_path, file_name)) => (dir_path, file_name),
+                None => ("", entry.path.as_str()),
+            };
+            if !file_name.eq_ignore_ascii_case("SKILL.md") {
+                return None;
+            }
 
-        let desc = metadata
-            .description
-            .clone()
-            .or(description)
-            .unwrap_or_else(|| format!("A skill from {}", folder_name));
+            let relative = if base_path.is_empty() {
+                dir_path
+            } else if dir_path == base_path {
+                ""
+            } else {
+                dir_path.strip_prefix(base_path)?.strip_prefix('/')?
+            };
+            let depth = if relative.is_empty() {
+                0
+            } else {
+                relative.split('/').count() as u32
+            };
+            if depth > MAX_SCAN_DEPTH {
+                return None;
+            }
 
-        let category = categorize_skill(&name, &desc, &metadata.tags);
+            Some((dir_path.to_string(), entry.path.clone()))
+        })
+        .collect();
+
+    found.sort_by(|(a, _), (b, _)| {
+        a.matches('/')
+            .count()
+            .cmp(&b.matches('/').count())
+            .then_with(|| a.cmp(b))
+    });
+
+    let mut skill_dirs = BTreeSet::<String>::new();
+    found.retain(|(dir_path, _)| {
+        let nested = skill_dirs
+            .iter()
+            .any(|parent| parent.is_empty() || dir_path.starts_with(&format!("{}/", parent)));
+        !nested && skill_dirs.insert(dir_path.clone())
+    });
+
+    Some(found)
+}
 
-        let id = if dir_path.is_empty() {
-            format!("{}/{}", owner, repo)
-        } else {
-            format!("{}/{}/{}", owner, repo, dir_path)
-        };
+/// Whether a recursive tree has an entry at or below `path`; the empty
+/// path is the root of the tree
+fn contains_path(tree: &GitHubTree, path: &str) -> bool {
+    path.is_empty()
+        || tree.tree.iter().any(|entry| {
+            entry
+                .path
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+}
+
+/// SHA of the entry at `path` in a recursive tree, or of the tree itself
+/// for an empty path
+pub fn tree_entry_sha(tree: &GitHubTree, path: &str) -> Option<String> {
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        return Some(tree.sha.clone());
+    }
+    tree.tree
+        .iter()
+        .find(|entry| entry.path == path)
+        .map(|entry| entry.sha.clone())
+}
 
-        Ok(Skill {
-            id,
-            name,
-            description: desc,
-            repository: format!("{}/{}", owner, repo),
-            git_ref: git_ref.map(|s| s.to_string()),
-            path: folder_name,
-            category,
-            readme: Some(content),
-            metadata: Some(metadata),
-            installed_at: None,
+/// Files below `dir_path` in a recursive tree, as `(relative path, blob SHA)`
+/// pairs. Symlinks are blobs holding the link target, as the tarball has them.
+pub fn directory_blobs(tree: &GitHubTree, dir_path: &str) -> Vec<(String, String)> {
+    let dir_path = dir_path.trim_matches('/');
+    tree.tree
+        .iter()
+        .filter(|entry| entry.entry_type == "blob")
+        .filter_map(|entry| {
+            let relative = if dir_path.is_empty() {
+                entry.path.as_str()
+            } else {
+                entry.path.strip_prefix(dir_path)?.strip_prefix('/')?
+            };
+            Some((relative.to_string(), entry.sha.clone()))
         })
+        .collect()
+}
+
+/// Build a store skill from the SKILL.md content of a skill directory
+fn build_skill(
+    owner: &str,
+    repo: &str,
+    dir_path: &str,
+    git_ref: Option<&str>,
+    content: String,
+) -> Skill {
+    // Parse frontmatter and content
+    let (metadata, description) = parse_skill_md(&content);
+
+    let folder_name = if dir_path.is_empty() {
+        repo.to_string()
+    } else {
+        dir_path.rsplit('/').next().unwrap_or("skill").to_string()
+    };
+
+    let name = metadata
+        .name
+        .clone()
+        .unwrap_or_else(|| folder_name.replace('-', " "));
+
+    let desc = metadata
+        .description
+        .clone()
+        .or(description)
+        .unwrap_or_else(|| format!("A skill from {}", folder_name));
+
+    let category = categorize_skill(&name, &desc, &metadata.tags);
+
+    let id = if dir_path.is_empty() {
+        format!("{}/{}", owner, repo)
+    } else {
+        format!("{}/{}/{}", owner, repo, dir_path)
+    };
+
+    Skill {
+        id,
+        name,
+        description: desc,
+        repository: format!("{}/{}", owner, repo),
+        git_ref: git_ref.map(|s| s.to_string()),
+        path: folder_name,
+        category,
+        readme: Some(content),
+        metadata: Some(metadata),
+        installed_at: None,
     }
 }
 
@@ -550,8 +1346,354 @@ fn categorize_skill(name: &str, desc: &str, tags: &Option<Vec<String>>) -> Skill
     }
 }
 
+/// Parse an `owner/repo` repository string
+pub(crate) fn split_repository(repository: &str) -> Option<(&str, &str)> {
+    let (owner, repo) = repository.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some((owner, repo))
+}
+
 impl Default for GitHubService {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// Statuses GitHub returns while briefly unavailable
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Full-jitter backoff: a random delay up to the exponential backoff for
+/// this attempt, capped at the policy's maximum delay
+fn backoff_delay(policy: &RetryPolicy, attempt: u32, rng: &mut impl Rng) -> Duration {
+    let exponential = policy
+        .base_delay_ms
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(32));
+    let cap = exponential.min(policy.max_delay_ms);
+    Duration::from_millis(rng.gen_range(0..=cap))
+}
+
+/// Backoff before retrying a request that failed `attempts` times, or
+/// `None` when the attempts are used up or the retry would start after
+/// the overall deadline
+fn retry_delay(
+    policy: &RetryPolicy,
+    attempts: u32,
+    now: Instant,
+    deadline: Instant,
+    rng: &mut impl Rng,
+) -> Option<Duration> {
+    if attempts >= policy.max_attempts.max(1) {
+        return None;
+    }
+    let delay = backoff_delay(policy, attempts, rng);
+    (now + delay < deadline).then_some(delay)
+}
+
+/// GitHub answers both rate limits and missing permissions with 403 (or 429)
+fn is_forbidden(status: StatusCode) -> bool {
+    status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn header_value<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Core API quota from the `X-RateLimit-*` headers of a response
+fn rate_limit_from_headers(headers: &HeaderMap) -> Option<RateLimitInfo> {
+    // Search and GraphQL have quotas of their own
+    if let Some(resource) = header_value::<String>(headers, "x-ratelimit-resource") {
+        if resource != "core" {
+            return None;
+        }
+    }
+
+    let limit: u32 = header_value(headers, "x-ratelimit-limit")?;
+    let remaining: u32 = header_value(headers, "x-ratelimit-remaining")?;
+    let reset: i64 = header_value(headers, "x-ratelimit-reset")?;
+    let used = header_value(headers, "x-ratelimit-used").unwrap_or(limit - remaining.min(limit));
+
+    Some(RateLimitInfo {
+        limit,
+        remaining,
+        used,
+        reset_at: chrono::DateTime::from_timestamp(reset, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+    })
+}
+
+/// A response that keeps its request slot until the body has been read,
+/// so downloads count against the concurrency limit and not just requests
+struct SlotResponse {
+    response: Response,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl SlotResponse {
+    async fn text(self) -> Result<String, reqwest::Error> {
+        self.response.text().await
+    }
+
+    async fn bytes(self) -> Result<Vec<u8>, reqwest::Error> {
+        Ok(self.response.bytes().await?.to_vec())
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(self) -> Result<T, reqwest::Error> {
+        self.response.json().await
+    }
+
+    async fn forbidden_error(self, url: &str) -> GitHubError {
+        let Self { response, _permit } = self;
+        forbidden_error(response, url).await
+    }
+}
+
+impl std::ops::Deref for SlotResponse {
+    type Target = Response;
+
+    fn deref(&self) -> &Response {
+        &self.response
+    }
+}
+
+impl std::ops::DerefMut for SlotResponse {
+    fn deref_mut(&mut self) -> &mut Response {
+        &mut self.response
+    }
+}
+
+/// Tell a primary or secondary rate limit apart from a request the token
+/// is not allowed to make, following GitHub's documented headers
+async fn forbidden_error(response: Response, url: &str) -> GitHubError {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v.get("message")?.as_str().map(str::to_string))
+        .unwrap_or(body);
+    let lower = message.to_lowercase();
+
+    let reset_at = header_value::<i64>(&headers, "x-ratelimit-reset")
+        .and_then(|reset| chrono::DateTime::from_timestamp(reset, 0))
+        .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::seconds(60));
+
+    if let Some(retry_after) = header_value::<u64>(&headers, "retry-after") {
+        return GitHubError::SecondaryRateLimited { retry_after };
+    }
+    if header_value::<u32>(&headers, "x-ratelimit-remaining") == Some(0) {
+        return GitHubError::RateLimited { reset_at };
+    }
+    if status == StatusCode::TOO_MANY_REQUESTS || lower.contains("secondary rate limit") {
+        // Without Retry-After, GitHub asks clients to wait at least a minute
+        return GitHubError::SecondaryRateLimited { retry_after: 60 };
+    }
+    if lower.contains("rate limit") {
+        return GitHubError::RateLimited { reset_at };
+    }
+
+    GitHubError::Forbidden(format!("{} ({})", url, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GitHubTreeEntry;
+    use crate::services::test_support::pairs;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn tree(paths: &[&str]) -> GitHubTree {
+        GitHubTree {
+            sha: "tree".to_string(),
+            tree: paths
+                .iter()
+                .map(|path| GitHubTreeEntry {
+                    path: path.to_string(),
+                    entry_type: "blob".to_string(),
+                    sha: "blob".to_string(),
+                    size: None,
+                })
+                .collect(),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn finds_skills_but_not_skills_nested_in_them() {
+        let tree = tree(&[
+            "README.md",
+            "skills/zeta/SKILL.md",
+            "skills/alpha/SKILL.md",
+            "skills/alpha/examples/SKILL.md",
+            "skills/alpha/docs/guide.md",
+        ]);
+
+        assert_eq!(
+            find_skill_md_paths(&tree, ""),
+            Some(pairs(&[
+                ("skills/alpha", "skills/alpha/SKILL.md"),
+                ("skills/zeta", "skills/zeta/SKILL.md"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn a_skill_at_the_base_path_hides_everything_below_it() {
+        let tree = tree(&["skills/SKILL.md", "skills/other/SKILL.md", "SKILL.md"]);
+
+        assert_eq!(
+            find_skill_md_paths(&tree, "skills"),
+            Some(pairs(&[("skills", "skills/SKILL.md")]))
+        );
+    }
+
+    #[test]
+    fn matches_the_skill_file_name_like_the_directory_walk() {
+        let tree = tree(&[
+            "lower/skill.md",
+            "backup/SKILL.md.bak",
+            "prefixed/MY-SKILL.md",
+        ]);
+
+        assert_eq!(
+            find_skill_md_paths(&tree, ""),
+            Some(pairs(&[("lower", "lower/skill.md")]))
+        );
+    }
+
+    #[test]
+    fn skips_skills_deeper_than_the_scan_depth_or_outside_the_base_path() {
+        let tree = tree(&[
+            "base/x/y/z/SKILL.md",
+            "base/p/q/r/s/SKILL.md",
+            "elsewhere/SKILL.md",
+        ]);
+
+        assert_eq!(
+            find_skill_md_paths(&tree, "base"),
+            Some(pairs(&[("base/x/y/z", "base/x/y/z/SKILL.md")]))
+        );
+    }
+
+    #[test]
+    fn a_base_path_must_be_a_directory_of_the_tree() {
+        let tree = tree(&["skills/demo/SKILL.md", "skillset/SKILL.md"]);
+
+        assert!(contains_path(&tree, ""));
+        assert!(contains_path(&tree, "skills"));
+        assert!(contains_path(&tree, "skills/demo"));
+        assert!(!contains_path(&tree, "skill"));
+        assert!(!contains_path(&tree, "missing"));
+    }
+
+    fn entry(path: &str, entry_type: &str, sha: &str) -> GitHubTreeEntry {
+        GitHubTreeEntry {
+            path: path.to_string(),
+            entry_type: entry_type.to_string(),
+            sha: sha.to_string(),
+            size: None,
+        }
+    }
+
+    #[test]
+    fn lists_the_blobs_below_a_directory() {
+        let tree = GitHubTree {
+            sha: "root".to_string(),
+            tree: vec![
+                entry("skills", "tree", "t1"),
+                entry("skills/demo", "tree", "t2"),
+                entry("skills/demo/SKILL.md", "blob", "b1"),
+                entry("skills/demo/scripts", "tree", "t3"),
+                entry("skills/demo/scripts/run.sh", "blob", "b2"),
+                entry("skills/demo/vendor", "commit", "c1"),
+                entry("skills/demo-extra/SKILL.md", "blob", "b3"),
+            ],
+            truncated: false,
+        };
+
+        assert_eq!(
+            directory_blobs(&tree, "/skills/demo/"),
+            pairs(&[("SKILL.md", "b1"), ("scripts/run.sh", "b2")])
+        );
+        assert_eq!(directory_blobs(&tree, "").len(), 3);
+        assert_eq!(tree_entry_sha(&tree, "skills/demo").as_deref(), Some("t2"));
+        assert_eq!(tree_entry_sha(&tree, "").as_deref(), Some("root"));
+        assert_eq!(tree_entry_sha(&tree, "skills/missing"), None);
+    }
+
+    fn policy(base_delay_ms: u64, max_delay_ms: u64) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay_ms,
+            max_delay_ms,
+            deadline_secs: 60,
+        }
+    }
+
+    #[test]
+    fn backoff_stays_within_the_exponential_bound() {
+        let policy = policy(100, 1_000);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for (attempt, bound) in [(1, 100), (2, 200), (3, 400), (4, 800), (5, 1_000)] {
+            for _ in 0..1_000 {
+                let delay = backoff_delay(&policy, attempt, &mut rng);
+                assert!(delay <= Duration::from_millis(bound), "attempt {}", attempt);
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped_at_the_maximum_delay() {
+        let policy = policy(u64::MAX / 2, 8_000);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for attempt in [1, 10, 40, u32::MAX] {
+            let delay = backoff_delay(&policy, attempt, &mut rng);
+            assert!(delay <= Duration::from_millis(8_000));
+        }
+    }
+
+    #[test]
+    fn retries_stop_after_the_last_attempt() {
+        let policy = policy(0, 0);
+        let now = Instant::now();
+        let deadline = now + Duration::from_secs(60);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert_eq!(
+            retry_delay(&policy, 3, now, deadline, &mut rng),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_delay(&policy, 4, now, deadline, &mut rng), None);
+    }
+
+    #[test]
+    fn retries_stop_when_the_backoff_would_pass_the_deadline() {
+        let policy = policy(1_000, 1_000);
+        let now = Instant::now();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..100 {
+            let before = now + Duration::from_millis(1_001);
+            assert!(retry_delay(&policy, 1, now, before, &mut rng).is_some());
+            assert_eq!(retry_delay(&policy, 1, now, now, &mut rng), None);
+        }
+    }
+
+    #[test]
+    fn truncated_trees_are_walked_instead() {
+        let mut tree = tree(&["skills/alpha/SKILL.md"]);
+        tree.truncated = true;
+
+        assert_eq!(find_skill_md_paths(&tree, ""), None);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::models::{LockedSkill, Lockfile, PlanAction, PlanItem, SkillsPlan};
+use crate::services::skill::{hash_files, SkillError, SkillService};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name used when a directory is given as the lockfile location
+pub const LOCKFILE_NAME: &str = "skills.lock";
+
+/// Format version written into new lockfiles
+const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum LockError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse error: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Serialize error: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Lockfile not found: {0}")]
+    NotFound(String),
+    #[error("Unsupported lockfile version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("Content hash mismatch for '{skill}': lockfile has {expected}, downloaded {actual}")]
+    HashMismatch {
+        skill: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Home directory not found")]
+    HomeNotFound,
+    #[error(transparent)]
+    Skill(#[from] SkillError),
+}
+
+pub struct LockService;
+
+impl LockService {
+    /// Resolve a lockfile location. `path` may be the file itself or a
+    /// directory to put `skills.lock` in; the home directory is used when omitted.
+    pub fn resolve_path(path: Option<&str>) -> Result<PathBuf, LockError> {
+        let path = match path.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None => dirs::home_dir().ok_or(LockError::HomeNotFound)?,
+        };
+
+        if path.is_dir() {
+            Ok(path.join(LOCKFILE_NAME))
+        } else {
+            Ok(path)
+        }
+    }
+
+    /// Load a lockfile
+    pub fn load(path: &Path) -> Result<Lockfile, LockError> {
+        if !path.is_file() {
+            return Err(LockError::NotFound(path.to_string_lossy().to_string()));
+        }
+
+        let lockfile: Lockfile = toml::from_str(&fs::read_to_string(path)?)?;
+        if lockfile.version > LOCKFILE_VERSION {
+            return Err(LockError::UnsupportedVersion(lockfile.version));
+        }
+
+        Ok(lockfile)
+    }
+
+    /// Pin every installed skill with a known source commit and write the lockfile
+    pub fn generate(path: &Path) -> Result<Lockfile, LockError> {
+        let mut skills = Vec::new();
+
+        for skill in SkillService::list_installed()? {
+            // Skills created locally or installed by hand cannot be reproduced
+            let Some(origin) = skill.origin else {
+                continue;
+            };
+            let Some(commit) = origin.commit_sha else {
+                continue;
+            };
+            let Some(manifest) = SkillService::get_manifest(&skill.id)? else {
+                continue;
+            };
+
+            skills.push(LockedSkill {
+                name: skill.id,
+                repository: origin.repository,
+                path: origin.path,
+                git_ref: origin.git_ref,
+                commit,
+                content_hash: content_hash(&manifest.files),
+            });
+        }
+
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let lockfile = Lockfile {
+            version: LOCKFILE_VERSION,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            skills,
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(&lockfile)?)?;
+
+        Ok(lockfile)
+    }
+
+    /// Work out which skills must be installed or updated to match a lockfile
+    pub fn plan(lockfile: &Lockfile, path: &Path) -> Result<SkillsPlan, LockError> {
+        let mut items = Vec::new();
+
+        for locked in &lockfile.skills {
+            let mut item = PlanItem {
+                skill_name: locked.name.clone(),
+                action: PlanAction::Install,
+                repository: Some(locked.repository.clone()),
+                path: Some(locked.path.clone()),
+                git_ref: locked.git_ref.clone(),
+                installed_commit: None,
+                target_commit: Some(locked.commit.clone()),
+                content_hash: Some(locked.content_hash.clone()),
+                reason: None,
+            };
+
+            if SkillService::is_installed(&locked.name)? {
+                let manifest = SkillService::get_manifest(&locked.name)?;
+                match manifest
+                    .as_ref()
+                    .and_then(|m| m.origin.as_ref().map(|origin| (m, origin)))
+                {
+                    Some((manifest, origin))
+                        if origin.repository.eq_ignore_ascii_case(&locked.repository)
+                            && origin.path == locked.path =>
+                    {
+                        item.installed_commit = origin.commit_sha.clone();
+                        item.action = if origin.commit_sha.as_deref()
+                            == Some(locked.commit.as_str())
+                            && content_hash(&manifest.files) == locked.content_hash
+                        {
+                            PlanAction::Unchanged
+                        } else {
+                            PlanAction::Update
+                        };
+                    }
+                    Some((_, origin)) => {
+                        item.action = PlanAction::Conflict;
+                        item.reason = Some(format!(
+                            "Installed from {}/{}",
+                            origin.repository, origin.path
+                        ));
+                    }
+                    None => {
+                        item.action = PlanAction::Conflict;
+                        item.reason = Some("Installed without a recorded source".to_string());
+                    }
+                }
+            }
+
+            items.push(item);
+        }
+
+        Ok(SkillsPlan {
+            manifest_path: path.to_string_lossy().to_string(),
+            items,
+        })
+    }
+
+    /// Check downloaded files against the content hash recorded in a lockfile
+    pub fn verify(
+        skill_name: &str,
+        files: &[(String, Vec<u8>)],
+        expected: &str,
+    ) -> Result<(), LockError> {
+        let actual = content_hash(&hash_files(files));
+        if actual != expected {
+            return Err(LockError::HashMismatch {
+                skill: skill_name.to_string(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Hash the per-file hashes of a skill into a single content hash
+pub fn content_hash(file_hashes: &BTreeMap<String, String>) -> String {
+    let mut hasher = Sha256::new();
+    for (path, hash) in file_hashes {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(hash.as_bytes());
+        hasher.update([0]);
+    }
+    format!("sha256:{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::files;
+
+    fn hash_of(entries: &[(&str, &str)]) -> String {
+        let files: Vec<_> = files(entries);
+        content_hash(&hash_files(&files))
+    }
+
+    #[test]
+    fn content_hash_ignores_file_order_and_separators() {
+        let hash = hash_of(&[("SKILL.md", "# Skill\n"), ("scripts/run.sh", "echo hi\n")]);
+
+        let reordered = hash_of(&[("scripts/run.sh", "echo hi\n"), ("SKILL.md", "# Skill\n")]);
+        let windows = hash_of(&[("scripts\\run.sh", "echo hi\n"), ("SKILL.md", "# Skill\n")]);
+
+        assert!(hash.starts_with("sha256:"));
+        assert_eq!(hash, reordered);
+        assert_eq!(hash, windows);
+    }
+
+    #[test]
+    fn content_hash_depends_on_paths() {
+        let hash = hash_of(&[("a.md", "x")]);
+        let renamed = hash_of(&[("b.md", "x")]);
+
+        assert_ne!(hash, renamed);
+    }
+
+    #[test]
+    fn verify_flags_a_modified_file() {
+        let original: Vec<_> = files(&[("SKILL.md", "# Skill\n"), ("notes.md", "a\n")]);
+        let expected = content_hash(&hash_files(&original));
+        assert!(LockService::verify("demo", &original, &expected).is_ok());
+
+        let modified: Vec<_> = files(&[("SKILL.md", "# Skill\n"), ("notes.md", "b\n")]);
+        match LockService::verify("demo", &modified, &expected) {
+            Err(LockError::HashMismatch {
+                skill,
+                expected: locked,
+                actual,
+            }) => {
+                assert_eq!(skill, "demo");
+                assert_eq!(locked, expected);
+                assert_ne!(actual, expected);
+            }
+            other => panic!("expected a hash mismatch, got {:?}", other),
+        }
+    }
+}
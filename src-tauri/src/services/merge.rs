@@ -0,0 +1,470 @@
+use crate::models::{ConflictResolution, FileMergeResult, FileMergeStatus};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Files of one version of a skill, keyed by relative path
+pub type SkillFiles = BTreeMap<String, Vec<u8>>;
+
+/// Larger inputs are not diffed line by line and are treated as a conflict
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Outcome of a three-way merge of skill files
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    pub files: SkillFiles,
+    pub results: Vec<FileMergeResult>,
+}
+
+/// Three-way merge the installed files (`mine`) with a new upstream version
+/// (`theirs`), using the originally installed version as common ancestor.
+///
+/// Unchanged files are not reported. `resolutions` picks how conflicts are
+/// resolved per path, defaulting to conflict markers.
+pub fn merge_skill_files(
+    base: &SkillFiles,
+    mine: &SkillFiles,
+    theirs: &SkillFiles,
+    resolutions: &HashMap<String, ConflictResolution>,
+) -> MergeOutcome {
+    let paths: BTreeSet<&String> = base
+        .keys()
+        .chain(mine.keys())
+        .chain(theirs.keys())
+        .collect();
+
+    let mut files = SkillFiles::new();
+    let mut results = Vec::new();
+
+    for path in paths {
+        let b = base.get(path);
+        let m = mine.get(path);
+        let t = theirs.get(path);
+
+        let (content, status) = if m == t {
+            (m, None)
+        } else if m == b {
+            let status = if t.is_some() {
+                FileMergeStatus::Updated
+            } else {
+                FileMergeStatus::Removed
+            };
+            (t, Some(status))
+        } else if t == b {
+            (m, Some(FileMergeStatus::Local))
+        } else {
+            let resolution = resolutions.get(path).copied().unwrap_or_default();
+            let (content, markers) = resolve_conflict(b, m, t, resolution);
+
+            let status = if markers == Some(0) {
+                FileMergeStatus::Merged
+            } else {
+                FileMergeStatus::Conflict
+            };
+
+            if let Some(content) = content {
+                files.insert(path.clone(), content);
+            }
+            results.push(FileMergeResult {
+                path: path.clone(),
+                status: status.clone(),
+                resolution: (status == FileMergeStatus::Conflict).then_some(resolution),
+                conflict_markers: markers.filter(|count| *count > 0),
+            });
+            continue;
+        };
+
+        if let Some(content) = content {
+            files.insert(path.clone(), content.clone());
+        }
+        if let Some(status) = status {
+            results.push(FileMergeResult {
+                path: path.clone(),
+                status,
+                resolution: None,
+                conflict_markers: None,
+            });
+        }
+    }
+
+    MergeOutcome { files, results }
+}
+
+/// Resolve a file changed on both sides. Returns the content to keep (if any)
+/// and, for line merges, the number of conflict blocks written.
+fn resolve_conflict(
+    base: Option<&Vec<u8>>,
+    mine: Option<&Vec<u8>>,
+    theirs: Option<&Vec<u8>>,
+    resolution: ConflictResolution,
+) -> (Option<Vec<u8>>, Option<usize>) {
+    match resolution {
+        ConflictResolution::Mine => (mine.cloned(), None),
+        ConflictResolution::Theirs => (theirs.cloned(), None),
+        ConflictResolution::Merge => {
+            // Modify/delete conflicts keep whichever side still has the file
+            let (Some(mine), Some(theirs)) = (mine, theirs) else {
+                return (mine.or(theirs).cloned(), None);
+            };
+
+            let empty = Vec::new();
+            let base = base.unwrap_or(&empty);
+
+            match (
+                std::str::from_utf8(base),
+                std::str::from_utf8(mine),
+                std::str::from_utf8(theirs),
+            ) {
+                (Ok(b), Ok(m), Ok(t)) => match merge_text(b, m, t) {
+                    Some((merged, conflicts)) => (Some(merged.into_bytes()), Some(conflicts)),
+                    // Too large to diff, keep the local file
+                    None => (Some(mine.clone()), None),
+                },
+                // Binary files cannot be merged, keep the local file
+                _ => (Some(mine.clone()), None),
+            }
+        }
+    }
+}
+
+/// A change to base lines `start..end`, replaced with `lines`
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+/// Line-based three-way merge. Returns the merged text and the number of
+/// conflict blocks, or `None` when the inputs are too large to diff.
+pub fn merge_text(base: &str, mine: &str, theirs: &str) -> Option<(String, usize)> {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let mine_lines: Vec<&str> = mine.split_inclusive('\n').collect();
+    let theirs_lines: Vec<&str> = theirs.split_inclusive('\n').collect();
+
+    let mine_hunks = diff_hunks(&base_lines, &mine_lines)?;
+    let theirs_hunks = diff_hunks(&base_lines, &theirs_lines)?;
+
+    let mut output = String::new();
+    let mut conflicts = 0;
+    let mut cursor = 0;
+    let (mut i, mut j) = (0, 0);
+
+    while i < mine_hunks.len() || j < theirs_hunks.len() {
+        // Start a group with whichever hunk comes first, then pull in every
+        // hunk from either side that overlaps or touches it
+        let take_mine = j >= theirs_hunks.len()
+            || (i < mine_hunks.len() && mine_hunks[i].start <= theirs_hunks[j].start);
+        let (lo, mut hi) = if take_mine {
+            (mine_hunks[i].start, mine_hunks[i].end)
+        } else {
+            (theirs_hunks[j].start, theirs_hunks[j].end)
+        };
+        let (mine_from, theirs_from) = (i, j);
+
+        loop {
+            if i < mine_hunks.len() && mine_hunks[i].start <= hi {
+                hi = hi.max(mine_hunks[i].end);
+                i += 1;
+            } else if j < theirs_hunks.len() && theirs_hunks[j].start <= hi {
+                hi = hi.max(theirs_hunks[j].end);
+                j += 1;
+            } else {
+                break;
+            }
+        }
+
+        for line in &base_lines[cursor..lo] {
+            output.push_str(line);
+        }
+        cursor = hi;
+
+        let mine_group = &mine_hunks[mine_from..i];
+        let theirs_group = &theirs_hunks[theirs_from..j];
+
+        if theirs_group.is_empty() {
+            output.push_str(&apply_hunks(&base_lines, lo, hi, mine_group));
+            continue;
+        }
+        if mine_group.is_empty() {
+            output.push_str(&apply_hunks(&base_lines, lo, hi, theirs_group));
+            continue;
+        }
+
+        let mine_text = apply_hunks(&base_lines, lo, hi, mine_group);
+        let theirs_text = apply_hunks(&base_lines, lo, hi, theirs_group);
+
+        if mine_text == theirs_text {
+            output.push_str(&mine_text);
+            continue;
+        }
+
+        conflicts += 1;
+        output.push_str("<<<<<<< mine\n");
+        push_block(&mut output, &mine_text);
+        output.push_str("=======\n");
+        push_block(&mut output, &theirs_text);
+        output.push_str(">>>>>>> theirs\n");
+    }
+
+    for line in &base_lines[cursor..] {
+        output.push_str(line);
+    }
+
+    Some((output, conflicts))
+}
+
+/// Append a conflict block, making sure the next marker starts on its own line
+fn push_block(output: &mut String, text: &str) {
+    output.push_str(text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        output.push('\n');
+    }
+}
+
+/// Render base lines `lo..hi` with the given hunks of one side applied
+fn apply_hunks(base: &[&str], lo: usize, hi: usize, hunks: &[Hunk]) -> String {
+    let mut text = String::new();
+    let mut cursor = lo;
+
+    for hunk in hunks {
+        for line in &base[cursor..hunk.start] {
+            text.push_str(line);
+        }
+        for line in &hunk.lines {
+            text.push_str(line);
+        }
+        cursor = hunk.end;
+    }
+
+    for line in &base[cursor..hi] {
+        text.push_str(line);
+    }
+
+    text
+}
+
+/// Compute the changes from `base` to `other` via a longest common subsequence
+fn diff_hunks<'a>(base: &[&str], other: &[&'a str]) -> Option<Vec<Hunk<'a>>> {
+    let (n, m) = (base.len(), other.len());
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    // lcs[i][j] = length of the LCS of base[i..] and other[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if base[i] == other[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut hunk_i, mut hunk_j) = (0, 0);
+
+    while i < n || j < m {
+        if i < n && j < m && base[i] == other[j] {
+            if hunk_i < i || hunk_j < j {
+                hunks.push(Hunk {
+                    start: hunk_i,
+                    end: i,
+                    lines: other[hunk_j..j].to_vec(),
+                });
+            }
+            i += 1;
+            j += 1;
+            hunk_i = i;
+            hunk_j = j;
+        } else if j >= m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    if hunk_i < n || hunk_j < m {
+        hunks.push(Hunk {
+            start: hunk_i,
+            end: n,
+            lines: other[hunk_j..m].to_vec(),
+        });
+    }
+
+    Some(hunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_support::files;
+
+    fn merge(base: &SkillFiles, mine: &SkillFiles, theirs: &SkillFiles) -> MergeOutcome {
+        merge_skill_files(base, mine, theirs, &HashMap::new())
+    }
+
+    fn text(outcome: &MergeOutcome, path: &str) -> String {
+        String::from_utf8(outcome.files[path].clone()).unwrap()
+    }
+
+    #[test]
+    fn merges_edits_on_both_sides() {
+        let base = files(&[("SKILL.md", "a\nb\nc\nd\ne\n")]);
+        let mine = files(&[("SKILL.md", "A\nb\nc\nd\ne\n")]);
+        let theirs = files(&[("SKILL.md", "a\nb\nc\nd\nE\n")]);
+
+        let outcome = merge(&base, &mine, &theirs);
+
+        assert_eq!(text(&outcome, "SKILL.md"), "A\nb\nc\nd\nE\n");
+        assert_eq!(outcome.results.len(), 1);
+        assert_eq!(outcome.results[0].status, FileMergeStatus::Merged);
+        assert_eq!(outcome.results[0].conflict_markers, None);
+    }
+
+    #[test]
+    fn takes_one_sided_changes_without_merging() {
+        let base = files(&[("a.md", "a\n"), ("b.md", "b\n"), ("c.md", "c\n")]);
+        let mine = files(&[("a.md", "mine\n"), ("b.md", "b\n"), ("c.md", "c\n")]);
+        let theirs = files(&[("a.md", "a\n"), ("b.md", "theirs\n")]);
+
+        let outcome = merge(&base, &mine, &theirs);
+
+        assert_eq!(text(&outcome, "a.md"), "mine\n");
+        assert_eq!(text(&outcome, "b.md"), "theirs\n");
+        assert!(!outcome.files.contains_key("c.md"));
+        let statuses: Vec<_> = outcome
+            .results
+            .iter()
+            .map(|r| (r.path.as_str(), r.status.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("a.md", FileMergeStatus::Local),
+                ("b.md", FileMergeStatus::Updated),
+                ("c.md", FileMergeStatus::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_overlapping_edits_as_conflict() {
+        let base = files(&[("SKILL.md", "a\nb\nc\n")]);
+        let mine = files(&[("SKILL.md", "a\nmine\nc\n")]);
+        let theirs = files(&[("SKILL.md", "a\ntheirs\nc\n")]);
+
+        let outcome = merge(&base, &mine, &theirs);
+
+        assert_eq!(
+            text(&outcome, "SKILL.md"),
+            "a\n<<<<<<< mine\nmine\n=======\ntheirs\n>>>>>>> theirs\nc\n"
+        );
+        assert_eq!(outcome.results[0].status, FileMergeStatus::Conflict);
+        assert_eq!(outcome.results[0].conflict_markers, Some(1));
+        assert_eq!(
+            outcome.results[0].resolution,
+            Some(ConflictResolution::Merge)
+        );
+    }
+
+    #[test]
+    fn resolves_conflicts_as_requested() {
+        let base = files(&[("SKILL.md", "b\n")]);
+        let mine = files(&[("SKILL.md", "mine\n")]);
+        let theirs = files(&[("SKILL.md", "theirs\n")]);
+
+        for (resolution, expected) in [
+            (ConflictResolution::Mine, "mine\n"),
+            (ConflictResolution::Theirs, "theirs\n"),
+        ] {
+            let resolutions = HashMap::from([("SKILL.md".to_string(), resolution)]);
+            let outcome = merge_skill_files(&base, &mine, &theirs, &resolutions);
+            assert_eq!(text(&outcome, "SKILL.md"), expected);
+            assert_eq!(outcome.results[0].resolution, Some(resolution));
+        }
+    }
+
+    #[test]
+    fn keeps_the_modified_side_of_a_modify_delete_conflict() {
+        let base = files(&[("notes.md", "a\n")]);
+        let modified = files(&[("notes.md", "changed\n")]);
+        let deleted = SkillFiles::new();
+
+        for (mine, theirs) in [(&deleted, &modified), (&modified, &deleted)] {
+            let outcome = merge(&base, mine, theirs);
+            assert_eq!(text(&outcome, "notes.md"), "changed\n");
+            assert_eq!(outcome.results[0].status, FileMergeStatus::Conflict);
+            assert_eq!(outcome.results[0].conflict_markers, None);
+        }
+    }
+
+    #[test]
+    fn merges_files_added_on_both_sides() {
+        let base = SkillFiles::new();
+
+        let same = files(&[("new.md", "same\n")]);
+        let outcome = merge(&base, &same, &same);
+        assert_eq!(text(&outcome, "new.md"), "same\n");
+        assert!(outcome.results.is_empty());
+
+        let mine = files(&[("new.md", "mine\n")]);
+        let theirs = files(&[("new.md", "theirs\n")]);
+        let outcome = merge(&base, &mine, &theirs);
+        assert_eq!(
+            text(&outcome, "new.md"),
+            "<<<<<<< mine\nmine\n=======\ntheirs\n>>>>>>> theirs\n"
+        );
+        assert_eq!(outcome.results[0].conflict_markers, Some(1));
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let (merged, conflicts) = merge_text(
+            "a\r\nb\r\nc\r\nd\r\n",
+            "A\r\nb\r\nc\r\nd\r\n",
+            "a\r\nb\r\nc\r\nD\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(merged, "A\r\nb\r\nc\r\nD\r\n");
+        assert_eq!(conflicts, 0);
+    }
+
+    #[test]
+    fn handles_a_missing_trailing_newline() {
+        let (merged, conflicts) = merge_text("a\nb\nc", "A\nb\nc", "a\nb\nC").unwrap();
+        assert_eq!(merged, "A\nb\nC");
+        assert_eq!(conflicts, 0);
+
+        // Conflict markers still start on their own line
+        let (merged, conflicts) = merge_text("a", "mine", "theirs").unwrap();
+        assert_eq!(
+            merged,
+            "<<<<<<< mine\nmine\n=======\ntheirs\n>>>>>>> theirs\n"
+        );
+        assert_eq!(conflicts, 1);
+    }
+
+    #[test]
+    fn keeps_the_local_file_when_too_large_to_diff() {
+        let lines = (MAX_DIFF_CELLS as f64).sqrt() as usize + 1;
+        let big = |edit: &str| {
+            let mut text: String = (0..lines).map(|i| format!("line {}\n", i)).collect();
+            text.push_str(edit);
+            text
+        };
+        let (base, mine, theirs) = (big(""), big("mine\n"), big("theirs\n"));
+
+        assert!(merge_text(&base, &mine, &theirs).is_none());
+
+        let outcome = merge(
+            &files(&[("big.md", &base)]),
+            &files(&[("big.md", &mine)]),
+            &files(&[("big.md", &theirs)]),
+        );
+        assert_eq!(text(&outcome, "big.md"), mine);
+        assert_eq!(outcome.results[0].status, FileMergeStatus::Conflict);
+        assert_eq!(outcome.results[0].conflict_markers, None);
+    }
+}
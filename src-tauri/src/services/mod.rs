@@ -1,9 +1,25 @@
+pub mod archive;
 pub mod cache;
+pub mod catalog;
 pub mod config;
+pub mod credentials;
 pub mod github;
+pub mod lock;
+pub mod merge;
+pub mod operations;
 pub mod skill;
+pub mod team;
+#[cfg(test)]
+mod test_support;
+pub mod trash;
+pub mod version;
 
 pub use cache::CacheService;
 pub use config::ConfigService;
+pub use credentials::CredentialService;
 pub use github::GitHubService;
+pub use lock::LockService;
 pub use skill::SkillService;
+pub use team::TeamService;
+pub use trash::TrashService;
+pub use version::VersionService;
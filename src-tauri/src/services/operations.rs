@@ -0,0 +1,126 @@
+use crate::models::{OperationKind, ProgressDetail, ProgressEvent, ProgressStage};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Error message of an operation stopped by `cancel_operation`
+pub const CANCELLED: &str = "Operation cancelled";
+
+/// Receives the progress events of an operation
+pub type ProgressSink = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
+
+/// A running sync or install that reports progress and can be cancelled
+pub struct Operation {
+    id: String,
+    kind: OperationKind,
+    cancel: CancellationToken,
+    sink: ProgressSink,
+}
+
+impl Operation {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Report progress of the operation
+    pub fn emit(&self, stage: ProgressStage, detail: ProgressDetail) {
+        (self.sink)(ProgressEvent {
+            operation_id: self.id.clone(),
+            kind: self.kind,
+            stage,
+            detail,
+        });
+    }
+
+    /// Run `future` to completion, or drop it as soon as the operation is
+    /// cancelled and return `None`
+    pub async fn run<T>(&self, future: impl Future<Output = T>) -> Option<T> {
+        tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => None,
+            result = future => Some(result),
+        }
+    }
+}
+
+/// Operations in flight, by id, so they can be cancelled from another command
+#[derive(Default)]
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<String, CancellationToken>>,
+    /// Ids of the repositories each sync in flight loads
+    repositories: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl OperationRegistry {
+    /// Register a new operation. The frontend passes its own id so it can
+    /// cancel the operation before the command returns; one is generated
+    /// otherwise.
+    pub fn start(
+        &self,
+        id: Option<String>,
+        kind: OperationKind,
+        sink: ProgressSink,
+    ) -> Arc<Operation> {
+        let id = id.filter(|id| !id.trim().is_empty()).unwrap_or_else(|| {
+            let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+            format!("{:?}-{}", kind, nanos).to_lowercase()
+        });
+
+        let cancel = CancellationToken::new();
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.insert(id.clone(), cancel.clone());
+        }
+
+        Arc::new(Operation {
+            id,
+            kind,
+            cancel,
+            sink,
+        })
+    }
+
+    /// Cancel an operation; false when no operation has this id
+    pub fn cancel(&self, id: &str) -> bool {
+        let Ok(operations) = self.operations.lock() else {
+            return false;
+        };
+        match operations.get(id) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Record which repositories a sync loads, until it finishes
+    pub fn set_repositories(&self, id: &str, repository_ids: Vec<String>) {
+        if let Ok(mut repositories) = self.repositories.lock() {
+            repositories.insert(id.to_string(), repository_ids);
+        }
+    }
+
+    /// Whether a sync in flight loads this repository
+    pub fn is_syncing(&self, repository_id: &str) -> bool {
+        self.repositories.lock().is_ok_and(|repositories| {
+            repositories
+                .values()
+                .any(|ids| ids.iter().any(|id| id == repository_id))
+        })
+    }
+
+    /// Forget an operation once it has completed
+    pub fn finish(&self, id: &str) {
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.remove(id);
+        }
+        if let Ok(mut repositories) = self.repositories.lock() {
+            repositories.remove(id);
+        }
+    }
+}
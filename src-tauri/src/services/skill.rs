@@ -1,4 +1,8 @@
-use crate::models::InstalledSkill;
+use crate::models::{InstalledSkill, SkillManifest, SkillOrigin, TrashEntry};
+use crate::services::merge::SkillFiles;
+use crate::services::TrashService;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -16,8 +20,19 @@ pub enum SkillError {
     AlreadyInstalled(String),
     #[error("Home directory not found")]
     HomeNotFound,
+    #[error("Manifest error: {0}")]
+    Manifest(#[from] serde_json::Error),
 }
 
+/// Name of the staging directory created next to the skills directory
+const STAGING_DIR_NAME: &str = ".myskills-staging";
+
+/// Suffix of the hidden copy an installed skill is moved to while it is replaced
+const BACKUP_SUFFIX: &str = ".myskills-old";
+
+/// Provenance manifest stored inside each installed skill
+pub const MANIFEST_FILE_NAME: &str = ".myskills.json";
+
 pub struct SkillService;
 
 impl SkillService {
@@ -34,50 +49,55 @@ impl SkillService {
         Ok(skills_dir)
     }
 
-    /// List all installed skills
-    pub fn list_installed() -> Result<Vec<InstalledSkill>, SkillError> {
-        let skills_dir = Self::get_skills_dir()?;
-        let mut skills = Vec::new();
+    /// Get the directory where disabled skills are parked, outside of
+    /// Claude Code's skills directory
+    fn get_disabled_dir() -> Result<PathBuf, SkillError> {
+        let home = dirs::home_dir().ok_or(SkillError::HomeNotFound)?;
+        let disabled_dir = home.join(".myskills").join("disabled");
 
-        if !skills_dir.exists() {
-            return Ok(skills);
+        if !disabled_dir.exists() {
+            fs::create_dir_all(&disabled_dir)?;
         }
 
-        for entry in fs::read_dir(&skills_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        Ok(disabled_dir)
+    }
 
-            if path.is_dir() {
-                let skill_md = path.join("SKILL.md");
-                if skill_md.exists() {
-                    let name = path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown")
-                        .to_string();
-
-                    // Read SKILL.md for description
-                    let description = fs::read_to_string(&skill_md)
-                        .ok()
-                        .and_then(|content| extract_description(&content))
-                        .unwrap_or_else(|| format!("Skill: {}", name));
-
-                    // Get metadata for installed_at
-                    let metadata = fs::metadata(&path)?;
-                    let installed_at = metadata
-                        .created()
-                        .ok()
-                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs().to_string())
-                        .unwrap_or_else(|| "unknown".to_string());
-
-                    skills.push(InstalledSkill {
-                        id: name.clone(),
-                        name: name.replace('-', " "),
-                        description,
-                        path: path.to_string_lossy().to_string(),
-                        installed_at,
-                    });
+    /// Find where an installed skill lives, whether enabled or disabled
+    pub fn find_skill_path(skill_name: &str) -> Result<Option<PathBuf>, SkillError> {
+        let skill_path = Self::get_skills_dir()?.join(skill_name);
+        if skill_path.exists() {
+            return Ok(Some(skill_path));
+        }
+
+        let disabled_path = Self::get_disabled_dir()?.join(skill_name);
+        if disabled_path.exists() {
+            return Ok(Some(disabled_path));
+        }
+
+        Ok(None)
+    }
+
+    /// Find where an installed skill lives, failing if it is not installed
+    pub(crate) fn require_skill_path(skill_name: &str) -> Result<PathBuf, SkillError> {
+        Self::find_skill_path(skill_name)?
+            .ok_or_else(|| SkillError::NotFound(skill_name.to_string()))
+    }
+
+    /// List all installed skills, enabled and disabled
+    pub fn list_installed() -> Result<Vec<InstalledSkill>, SkillError> {
+        let mut skills = Vec::new();
+
+        for (dir, enabled) in [
+            (Self::get_skills_dir()?, true),
+            (Self::get_disabled_dir()?, false),
+        ] {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+
+                if path.is_dir() && backup_target(&path).is_none() {
+                    if let Some(skill) = read_installed_skill(&path, enabled)? {
+                        skills.push(skill);
+                    }
                 }
             }
         }
@@ -85,85 +105,389 @@ impl SkillService {
         Ok(skills)
     }
 
-    /// Check if a skill is installed
+    /// Check if a skill is installed (enabled or disabled)
     pub fn is_installed(skill_name: &str) -> Result<bool, SkillError> {
+        Ok(Self::find_skill_path(skill_name)?.is_some())
+    }
+
+    /// Disable a skill by parking it outside the skills directory, keeping
+    /// its files and local edits
+    pub fn disable_skill(skill_name: &str) -> Result<String, SkillError> {
+        let skill_path = Self::get_skills_dir()?.join(skill_name);
+        let disabled_path = Self::get_disabled_dir()?.join(skill_name);
+
+        if !skill_path.exists() {
+            return Err(SkillError::NotFound(skill_name.to_string()));
+        }
+        if disabled_path.exists() {
+            return Err(SkillError::AlreadyInstalled(skill_name.to_string()));
+        }
+
+        move_dir(&skill_path, &disabled_path)?;
+        Ok(disabled_path.to_string_lossy().to_string())
+    }
+
+    /// Enable a disabled skill by moving it back into the skills directory
+    pub fn enable_skill(skill_name: &str) -> Result<String, SkillError> {
+        let skill_path = Self::get_skills_dir()?.join(skill_name);
+        let disabled_path = Self::get_disabled_dir()?.join(skill_name);
+
+        if !disabled_path.exists() {
+            return Err(SkillError::NotFound(skill_name.to_string()));
+        }
+        if skill_path.exists() {
+            return Err(SkillError::AlreadyInstalled(skill_name.to_string()));
+        }
+
+        move_dir(&disabled_path, &skill_path)?;
+        Ok(skill_path.to_string_lossy().to_string())
+    }
+
+    /// Enable exactly the given skills and disable every other installed skill
+    ///
+    /// Moves are applied one by one and undone in reverse order if any of
+    /// them fails, so the skills directory is never left half switched.
+    /// Returns the skills that were enabled and disabled.
+    pub fn set_enabled_skills(
+        wanted: &HashSet<String>,
+    ) -> Result<(Vec<String>, Vec<String>), SkillError> {
         let skills_dir = Self::get_skills_dir()?;
-        let skill_path = skills_dir.join(skill_name);
-        Ok(skill_path.exists())
+        let disabled_dir = Self::get_disabled_dir()?;
+
+        let mut moves = Vec::new();
+        for skill in Self::list_installed()? {
+            let should_enable = wanted.contains(&skill.id);
+            if skill.enabled != should_enable {
+                moves.push((skill.id, should_enable));
+            }
+        }
+
+        let mut done: Vec<(PathBuf, PathBuf)> = Vec::new();
+        for (skill_name, enable) in &moves {
+            let (from, to) = if *enable {
+                (disabled_dir.join(skill_name), skills_dir.join(skill_name))
+            } else {
+                (skills_dir.join(skill_name), disabled_dir.join(skill_name))
+            };
+
+            let result = if to.exists() {
+                Err(SkillError::AlreadyInstalled(skill_name.clone()))
+            } else {
+                move_dir(&from, &to)
+            };
+
+            if let Err(e) = result {
+                for (from, to) in done.iter().rev() {
+                    let _ = move_dir(to, from);
+                }
+                return Err(e);
+            }
+            done.push((from, to));
+        }
+
+        let (enabled, disabled): (Vec<_>, Vec<_>) = moves.into_iter().partition(|(_, e)| *e);
+        Ok((
+            enabled.into_iter().map(|(name, _)| name).collect(),
+            disabled.into_iter().map(|(name, _)| name).collect(),
+        ))
+    }
+
+    /// Get the staging directory where installs are assembled.
+    ///
+    /// It lives next to the skills directory (same filesystem) so a finished
+    /// install can be moved into place with a single rename.
+    fn get_staging_dir() -> Result<PathBuf, SkillError> {
+        let skills_dir = Self::get_skills_dir()?;
+        let parent = skills_dir.parent().ok_or(SkillError::HomeNotFound)?;
+        let staging_dir = parent.join(STAGING_DIR_NAME);
+
+        if !staging_dir.exists() {
+            fs::create_dir_all(&staging_dir)?;
+        }
+
+        Ok(staging_dir)
+    }
+
+    /// Create a fresh, uniquely named staging directory for a skill
+    pub(crate) fn create_staging_path(skill_name: &str) -> Result<PathBuf, SkillError> {
+        let staging_dir = Self::get_staging_dir()?;
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let staging_path =
+            staging_dir.join(format!("{}-{}-{}", skill_name, std::process::id(), nanos));
+        fs::create_dir_all(&staging_path)?;
+        Ok(staging_path)
+    }
+
+    /// Remove leftovers of installs that crashed before being moved into
+    /// place. Skills whose replacement crashed halfway are put back first.
+    pub fn cleanup_staging() -> Result<usize, SkillError> {
+        for dir in [Self::get_skills_dir()?, Self::get_disabled_dir()?] {
+            Self::restore_backups(&dir)?;
+        }
+
+        let staging_dir = Self::get_staging_dir()?;
+        let mut removed = 0;
+
+        for entry in fs::read_dir(&staging_dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Put back skills left in their backup by a crash during `move_into_place`,
+    /// and delete backups whose replacement completed
+    fn restore_backups(dir: &Path) -> Result<(), SkillError> {
+        for entry in fs::read_dir(dir)? {
+            let backup_path = entry?.path();
+            let Some(skill_path) = backup_target(&backup_path) else {
+                continue;
+            };
+
+            if skill_path.exists() {
+                fs::remove_dir_all(&backup_path)?;
+            } else {
+                println!("[Rust] Restoring {} from its backup", skill_path.display());
+                fs::rename(&backup_path, &skill_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a complete skill, including its manifest, in a new staging directory
+    fn stage_skill(
+        skill_name: &str,
+        files: &[(String, Vec<u8>)],
+        manifest: &SkillManifest,
+    ) -> Result<PathBuf, SkillError> {
+        let staging_path = Self::create_staging_path(skill_name)?;
+
+        let result = write_skill_files(&staging_path, files)
+            .and_then(|_| write_manifest(&staging_path, manifest));
+
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&staging_path);
+            return Err(e);
+        }
+
+        Ok(staging_path)
     }
 
     /// Install a skill from content
+    ///
+    /// Files are written to a staging directory first and the finished skill
+    /// is renamed into the skills directory, so a failure never leaves a
+    /// partially written skill behind. A provenance manifest recording
+    /// `origin` and a hash of every file is written alongside them.
     pub fn install_skill(
         skill_name: &str,
         files: Vec<(String, Vec<u8>)>,
+        origin: Option<SkillOrigin>,
     ) -> Result<String, SkillError> {
         let skills_dir = Self::get_skills_dir()?;
         let skill_path = skills_dir.join(skill_name);
 
-        if skill_path.exists() {
+        if Self::is_installed(skill_name)? {
             return Err(SkillError::AlreadyInstalled(skill_name.to_string()));
         }
 
-        // Create skill directory
-        fs::create_dir_all(&skill_path)?;
+        let has_origin = origin.is_some();
+        let manifest = new_manifest(origin, &files);
+        let staging_path = Self::stage_skill(skill_name, &files, &manifest)?;
 
-        // Write files
-        for (relative_path, content) in files {
-            let relative = Path::new(&relative_path);
-            if relative.is_absolute()
-                || relative
-                    .components()
-                    .any(|c| matches!(c, std::path::Component::ParentDir))
-            {
-                return Err(SkillError::Io(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    format!("Invalid relative path: {}", relative_path),
-                )));
+        if let Err(e) = fs::rename(&staging_path, &skill_path) {
+            let _ = fs::remove_dir_all(&staging_path);
+            return Err(e.into());
+        }
+
+        // Keep the upstream version as the base for later three-way merges
+        if has_origin {
+            if let Err(e) = Self::save_pristine(skill_name, &files) {
+                eprintln!(
+                    "[Rust] Failed to save pristine copy of {}: {}",
+                    skill_name, e
+                );
             }
+        }
 
-            let file_path = skill_path.join(relative);
+        Ok(skill_path.to_string_lossy().to_string())
+    }
 
-            // Create parent directories if needed
-            if let Some(parent) = file_path.parent() {
-                fs::create_dir_all(parent)?;
-            }
+    /// Replace an installed skill with new content
+    ///
+    /// `files` is what gets installed (possibly merged with local edits) and
+    /// `upstream` is the unmodified source version, which becomes the new
+    /// pristine copy and is what the manifest hashes describe. The new
+    /// version is staged completely before the swap, and the old directory is
+    /// put back if the new one cannot be moved into place.
+    pub fn replace_skill(
+        skill_name: &str,
+        files: &[(String, Vec<u8>)],
+        upstream: &[(String, Vec<u8>)],
+        origin: Option<SkillOrigin>,
+    ) -> Result<String, SkillError> {
+        let skill_path = Self::require_skill_path(skill_name)?;
 
-            fs::write(&file_path, content)?;
-
-            #[cfg(unix)]
-            {
-                let rel_str = relative_path.replace('\\', "/");
-                let is_script = rel_str.split('/').any(|seg| seg == "scripts")
-                    || rel_str.ends_with(".sh")
-                    || rel_str.ends_with(".command");
-                if is_script {
-                    let mut perm = fs::metadata(&file_path)?.permissions();
-                    perm.set_mode(0o755);
-                    fs::set_permissions(&file_path, perm)?;
-                }
-            }
+        let manifest = new_manifest(origin, upstream);
+        let staging_path = Self::stage_skill(skill_name, files, &manifest)?;
+        Self::move_into_place(&staging_path, &skill_path)?;
+
+        if let Err(e) = Self::save_pristine(skill_name, upstream) {
+            eprintln!(
+                "[Rust] Failed to save pristine copy of {}: {}",
+                skill_name, e
+            );
         }
 
         Ok(skill_path.to_string_lossy().to_string())
     }
 
-    /// Uninstall a skill
-    pub fn uninstall_skill(skill_name: &str) -> Result<(), SkillError> {
-        let skills_dir = Self::get_skills_dir()?;
-        let skill_path = skills_dir.join(skill_name);
-
+    /// Move a fully staged skill to `skill_path`, swapping out any existing
+    /// directory. The previous directory is put back if the swap fails, and
+    /// the staging directory is removed on failure.
+    pub(crate) fn move_into_place(
+        staging_path: &Path,
+        skill_path: &Path,
+    ) -> Result<(), SkillError> {
         if !skill_path.exists() {
-            return Err(SkillError::NotFound(skill_name.to_string()));
+            if let Err(e) = fs::rename(staging_path, skill_path) {
+                let _ = fs::remove_dir_all(staging_path);
+                return Err(e.into());
+            }
+            return Ok(());
         }
 
-        fs::remove_dir_all(&skill_path)?;
+        // Kept next to the skill rather than in staging, which is wiped at
+        // startup, so a crash between the renames cannot lose the skill
+        let backup_path = backup_path(skill_path)?;
+
+        if let Err(e) = fs::rename(skill_path, &backup_path) {
+            let _ = fs::remove_dir_all(staging_path);
+            return Err(e.into());
+        }
+
+        if let Err(e) = fs::rename(staging_path, skill_path) {
+            let _ = fs::rename(&backup_path, skill_path);
+            let _ = fs::remove_dir_all(staging_path);
+            return Err(e.into());
+        }
+
+        let _ = fs::remove_dir_all(&backup_path);
+        Ok(())
+    }
+
+    /// Read the provenance manifest of an installed skill
+    pub fn get_manifest(skill_name: &str) -> Result<Option<SkillManifest>, SkillError> {
+        let skill_path = Self::require_skill_path(skill_name)?;
+        Ok(read_manifest(&skill_path))
+    }
+
+    /// Read all files of an installed skill, excluding the manifest
+    pub fn read_installed_files(skill_name: &str) -> Result<SkillFiles, SkillError> {
+        let skill_path = Self::require_skill_path(skill_name)?;
+        let mut files = read_dir_files(&skill_path)?;
+        files.remove(MANIFEST_FILE_NAME);
+        Ok(files)
+    }
+
+    /// Get the directory holding the originally installed copy of each skill
+    fn get_pristine_dir() -> Result<PathBuf, SkillError> {
+        let home = dirs::home_dir().ok_or(SkillError::HomeNotFound)?;
+        let pristine_dir = home.join(".myskills").join("pristine");
+
+        if !pristine_dir.exists() {
+            fs::create_dir_all(&pristine_dir)?;
+        }
+
+        Ok(pristine_dir)
+    }
+
+    /// Save the upstream version of a skill as the base for three-way merges
+    pub(crate) fn save_pristine(
+        skill_name: &str,
+        files: &[(String, Vec<u8>)],
+    ) -> Result<(), SkillError> {
+        let pristine_dir = Self::get_pristine_dir()?;
+        let pristine_path = pristine_dir.join(skill_name);
+        let temp_path = pristine_dir.join(format!(".{}.tmp", skill_name));
+
+        if temp_path.exists() {
+            fs::remove_dir_all(&temp_path)?;
+        }
+        fs::create_dir_all(&temp_path)?;
+
+        if let Err(e) = write_skill_files(&temp_path, files) {
+            let _ = fs::remove_dir_all(&temp_path);
+            return Err(e);
+        }
+
+        if pristine_path.exists() {
+            fs::remove_dir_all(&pristine_path)?;
+        }
+        fs::rename(&temp_path, &pristine_path)?;
+        Ok(())
+    }
+
+    /// Load the originally installed version of a skill, if one was kept
+    pub(crate) fn load_pristine(skill_name: &str) -> Result<Option<SkillFiles>, SkillError> {
+        let pristine_path = Self::get_pristine_dir()?.join(skill_name);
+
+        if !pristine_path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(read_dir_files(&pristine_path)?))
+    }
+
+    /// Get the base version for a three-way merge of an installed skill
+    ///
+    /// Uses the pristine copy when one was kept. Otherwise files whose hash
+    /// still matches the manifest are known to be unmodified and serve as
+    /// their own base.
+    pub fn load_merge_base(
+        skill_name: &str,
+        manifest: &SkillManifest,
+        installed: &SkillFiles,
+    ) -> Result<SkillFiles, SkillError> {
+        if let Some(pristine) = Self::load_pristine(skill_name)? {
+            return Ok(pristine);
+        }
+
+        Ok(installed
+            .iter()
+            .filter(|(path, content)| {
+                manifest.files.get(*path) == Some(&format!("{:x}", Sha256::digest(content)))
+            })
+            .map(|(path, content)| (path.clone(), content.clone()))
+            .collect())
+    }
+
+    /// Uninstall a skill by moving it into the trash
+    pub fn uninstall_skill(skill_name: &str) -> Result<TrashEntry, SkillError> {
+        TrashService::move_to_trash(skill_name)
+    }
+
+    /// Drop the pristine copy of a skill
+    pub(crate) fn remove_pristine(skill_name: &str) -> Result<(), SkillError> {
+        let pristine_path = Self::get_pristine_dir()?.join(skill_name);
+        if pristine_path.exists() {
+            fs::remove_dir_all(&pristine_path)?;
+        }
         Ok(())
     }
 
     /// Read skill content from installed skill
     pub fn get_skill_content(skill_name: &str) -> Result<String, SkillError> {
-        let skills_dir = Self::get_skills_dir()?;
-        let skill_path = skills_dir.join(skill_name).join("SKILL.md");
+        let skill_path = Self::require_skill_path(skill_name)?.join("SKILL.md");
 
         if !skill_path.exists() {
             return Err(SkillError::NotFound(skill_name.to_string()));
@@ -174,6 +498,221 @@ impl SkillService {
     }
 }
 
+/// Hidden path next to a skill that holds it while it is replaced
+fn backup_path(skill_path: &Path) -> Result<PathBuf, SkillError> {
+    let name = skill_path
+        .file_name()
+        .ok_or_else(|| SkillError::NotFound(skill_path.display().to_string()))?;
+    Ok(skill_path.with_file_name(format!(".{}{}", name.to_string_lossy(), BACKUP_SUFFIX)))
+}
+
+/// The skill a backup made by `move_into_place` belongs to, or `None` for
+/// any other path
+fn backup_target(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let skill_name = name.strip_prefix('.')?.strip_suffix(BACKUP_SUFFIX)?;
+    (!skill_name.is_empty()).then(|| path.with_file_name(skill_name))
+}
+
+/// Read an installed skill directory, or `None` if it has no SKILL.md
+fn read_installed_skill(path: &Path, enabled: bool) -> Result<Option<InstalledSkill>, SkillError> {
+    let skill_md = path.join("SKILL.md");
+    if !skill_md.exists() {
+        return Ok(None);
+    }
+
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // Read SKILL.md for description
+    let description = fs::read_to_string(&skill_md)
+        .ok()
+        .and_then(|content| extract_description(&content))
+        .unwrap_or_else(|| format!("Skill: {}", name));
+
+    let manifest = read_manifest(path);
+
+    // Prefer the recorded install time, fall back to directory metadata
+    let installed_at = match &manifest {
+        Some(manifest) => manifest.installed_at.clone(),
+        None => fs::metadata(path)?
+            .created()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs().to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+    };
+
+    Ok(Some(InstalledSkill {
+        id: name.clone(),
+        name: name.replace('-', " "),
+        description,
+        path: path.to_string_lossy().to_string(),
+        installed_at,
+        enabled,
+        origin: manifest.and_then(|m| m.origin),
+    }))
+}
+
+/// Move a directory, copying it when a plain rename is not possible
+/// because the destination is on another filesystem
+pub(crate) fn move_dir(from: &Path, to: &Path) -> Result<(), SkillError> {
+    match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() != std::io::ErrorKind::CrossesDevices => return Err(e.into()),
+        Err(_) => {}
+    }
+
+    fs::create_dir(to)?;
+    if let Err(e) = copy_dir_contents(from, to).and_then(|_| fs::remove_dir_all(from)) {
+        let _ = fs::remove_dir_all(to);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Copy everything below `from` into the existing directory `to`, keeping
+/// file modes and symlinks
+fn copy_dir_contents(from: &Path, to: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else if file_type.is_dir() {
+            fs::create_dir(&target)?;
+            copy_dir_contents(&source, &target)?;
+        } else {
+            // Copies the permission bits along with the content
+            fs::copy(&source, &target)?;
+        }
+    }
+
+    fs::set_permissions(to, fs::metadata(from)?.permissions())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    let link = fs::read_link(source)?;
+    if fs::metadata(source).is_ok_and(|m| m.is_dir()) {
+        std::os::windows::fs::symlink_dir(link, target)
+    } else {
+        std::os::windows::fs::symlink_file(link, target)
+    }
+}
+
+/// Write skill files into a directory, rejecting paths that escape it
+pub(crate) fn write_skill_files(dir: &Path, files: &[(String, Vec<u8>)]) -> Result<(), SkillError> {
+    for (relative_path, content) in files {
+        let relative = Path::new(relative_path);
+        if relative.is_absolute()
+            || relative
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(SkillError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid relative path: {}", relative_path),
+            )));
+        }
+
+        let file_path = dir.join(relative);
+
+        // Create parent directories if needed
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(&file_path, content)?;
+
+        #[cfg(unix)]
+        {
+            let rel_str = relative_path.replace('\\', "/");
+            let is_script = rel_str.split('/').any(|seg| seg == "scripts")
+                || rel_str.ends_with(".sh")
+                || rel_str.ends_with(".command");
+            if is_script {
+                let mut perm = fs::metadata(&file_path)?.permissions();
+                perm.set_mode(0o755);
+                fs::set_permissions(&file_path, perm)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Read every file below a directory, keyed by `/`-separated relative path
+pub(crate) fn read_dir_files(dir: &Path) -> Result<SkillFiles, SkillError> {
+    let mut files = SkillFiles::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                let relative = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.insert(relative, fs::read(&path)?);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Build the provenance manifest for a set of source files
+fn new_manifest(origin: Option<SkillOrigin>, files: &[(String, Vec<u8>)]) -> SkillManifest {
+    SkillManifest {
+        origin,
+        installed_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: hash_files(files),
+    }
+}
+
+/// Compute the SHA-256 of each file, keyed by relative path
+pub(crate) fn hash_files(files: &[(String, Vec<u8>)]) -> BTreeMap<String, String> {
+    files
+        .iter()
+        .map(|(relative_path, content)| {
+            (
+                relative_path.replace('\\', "/"),
+                format!("{:x}", Sha256::digest(content)),
+            )
+        })
+        .collect()
+}
+
+/// Write the provenance manifest into a skill directory
+fn write_manifest(dir: &Path, manifest: &SkillManifest) -> Result<(), SkillError> {
+    let content = serde_json::to_string_pretty(manifest)?;
+    fs::write(dir.join(MANIFEST_FILE_NAME), content)?;
+    Ok(())
+}
+
+/// Read the provenance manifest of a skill directory, if it has a valid one
+fn read_manifest(dir: &Path) -> Option<SkillManifest> {
+    let content = fs::read_to_string(dir.join(MANIFEST_FILE_NAME)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
 /// Extract description from SKILL.md content
 fn extract_description(content: &str) -> Option<String> {
     // Check frontmatter for description
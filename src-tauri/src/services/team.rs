@@ -0,0 +1,251 @@
+use crate::models::{
+    ManifestRepository, ManifestSkill, PlanAction, PlanItem, SkillsPlan, TeamManifest,
+};
+use crate::services::config::ConfigService;
+use crate::services::github::{split_repository, GitHubError, GitHubService};
+use crate::services::skill::{SkillError, SkillService};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name looked up when a directory is given as the manifest location
+pub const TEAM_MANIFEST_FILE_NAME: &str = "skills.toml";
+
+#[derive(Error, Debug)]
+pub enum TeamError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse error: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Manifest not found: {0}")]
+    NotFound(String),
+    #[error("Invalid manifest: {0}")]
+    Invalid(String),
+    #[error("Home directory not found")]
+    HomeNotFound,
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+    #[error(transparent)]
+    Skill(#[from] SkillError),
+}
+
+pub struct TeamService;
+
+impl TeamService {
+    /// Locate a skills manifest. `path` may be the file itself or a directory
+    /// containing `skills.toml`; the home directory is used when omitted.
+    pub fn resolve_manifest_path(path: Option<&str>) -> Result<PathBuf, TeamError> {
+        let path = match path.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None => dirs::home_dir().ok_or(TeamError::HomeNotFound)?,
+        };
+
+        let path = if path.is_dir() {
+            path.join(TEAM_MANIFEST_FILE_NAME)
+        } else {
+            path
+        };
+
+        if !path.is_file() {
+            return Err(TeamError::NotFound(path.to_string_lossy().to_string()));
+        }
+
+        Ok(path)
+    }
+
+    /// Load and validate a skills manifest
+    pub fn load(path: &Path) -> Result<TeamManifest, TeamError> {
+        let content = fs::read_to_string(path)?;
+        let mut manifest: TeamManifest = toml::from_str(&content)?;
+
+        for repository in &mut manifest.repositories {
+            repository.url = repository.url.trim().trim_matches('/').to_string();
+            repository.base_path =
+                ConfigService::normalize_base_path(repository.base_path.as_deref());
+            repository.git_ref = ConfigService::normalize_git_ref(repository.git_ref.as_deref());
+
+            if split_repository(&repository.url).is_none() {
+                return Err(TeamError::Invalid(format!(
+                    "repository '{}' is not in owner/repo form",
+                    repository.url
+                )));
+            }
+        }
+
+        let mut urls = HashSet::new();
+        for repository in &manifest.repositories {
+            if !urls.insert(repository.url.to_lowercase()) {
+                return Err(TeamError::Invalid(format!(
+                    "repository '{}' is listed twice",
+                    repository.url
+                )));
+            }
+        }
+
+        let mut names = HashSet::new();
+        for skill in &mut manifest.skills {
+            skill.repository = skill.repository.trim().trim_matches('/').to_string();
+            skill.path = skill.path.trim().trim_matches('/').to_string();
+            skill.commit = ConfigService::normalize_git_ref(skill.commit.as_deref());
+
+            let repository = find_repository(&manifest.repositories, &skill.repository)?;
+            let (_, name) = skill_source(repository, skill);
+            if !names.insert(name.clone()) {
+                return Err(TeamError::Invalid(format!(
+                    "skill '{}' is listed twice",
+                    name
+                )));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Compare a manifest with the installed skills and work out what
+    /// applying it would install, update or remove
+    pub async fn plan(
+        github: &GitHubService,
+        manifest: &TeamManifest,
+        manifest_path: &Path,
+    ) -> Result<SkillsPlan, TeamError> {
+        // Resolved commits, keyed by repository and ref
+        let mut commits: HashMap<(String, Option<String>), String> = HashMap::new();
+        let mut listed = HashSet::new();
+        let mut items = Vec::new();
+
+        for skill in &manifest.skills {
+            let repository = find_repository(&manifest.repositories, &skill.repository)?;
+            let (owner, repo) = split_repository(&repository.url)
+                .ok_or_else(|| TeamError::Invalid(repository.url.clone()))?;
+            let (path, name) = skill_source(repository, skill);
+            listed.insert(name.clone());
+
+            let git_ref = skill.commit.clone().or_else(|| repository.git_ref.clone());
+            let key = (repository.url.clone(), git_ref.clone());
+            let target_commit = match commits.get(&key) {
+                Some(sha) => sha.clone(),
+                None => {
+                    let sha = github
+                        .resolve_commit_sha(owner, repo, git_ref.as_deref())
+                        .await?;
+                    commits.insert(key, sha.clone());
+                    sha
+                }
+            };
+
+            let mut item = PlanItem {
+                skill_name: name.clone(),
+                action: PlanAction::Install,
+                repository: Some(repository.url.clone()),
+                path: Some(path.clone()),
+                git_ref: repository.git_ref.clone(),
+                installed_commit: None,
+                target_commit: Some(target_commit.clone()),
+                content_hash: None,
+                reason: None,
+            };
+
+            if SkillService::is_installed(&name)? {
+                match SkillService::get_manifest(&name)?.and_then(|m| m.origin) {
+                    Some(origin)
+                        if origin.repository.eq_ignore_ascii_case(&repository.url)
+                            && origin.path == path =>
+                    {
+                        item.installed_commit = origin.commit_sha.clone();
+                        item.action = if origin.commit_sha.as_deref() == Some(&target_commit) {
+                            PlanAction::Unchanged
+                        } else {
+                            // A newer commit does not necessarily touch this skill
+                            let tree_sha = github
+                                .fetch_tree_sha(owner, repo, &path, Some(&target_commit))
+                                .await?;
+                            if origin.tree_sha.as_deref() == Some(tree_sha.as_str()) {
+                                PlanAction::Unchanged
+                            } else {
+                                PlanAction::Update
+                            }
+                        };
+                    }
+                    Some(origin) => {
+                        item.action = PlanAction::Conflict;
+                        item.reason = Some(format!(
+                            "Installed from {}/{}",
+                            origin.repository, origin.path
+                        ));
+                    }
+                    None => {
+                        item.action = PlanAction::Conflict;
+                        item.reason = Some("Installed without a recorded source".to_string());
+                    }
+                }
+            }
+
+            items.push(item);
+        }
+
+        if manifest.prune {
+            for skill in SkillService::list_installed()? {
+                if listed.contains(&skill.id) {
+                    continue;
+                }
+
+                // Skills created locally are never pruned
+                let Some(origin) = skill.origin else {
+                    continue;
+                };
+
+                items.push(PlanItem {
+                    skill_name: skill.id,
+                    action: PlanAction::Remove,
+                    repository: Some(origin.repository),
+                    path: Some(origin.path),
+                    git_ref: origin.git_ref,
+                    installed_commit: origin.commit_sha,
+                    target_commit: None,
+                    content_hash: None,
+                    reason: None,
+                });
+            }
+        }
+
+        Ok(SkillsPlan {
+            manifest_path: manifest_path.to_string_lossy().to_string(),
+            items,
+        })
+    }
+}
+
+/// Find the manifest repository a skill refers to
+fn find_repository<'a>(
+    repositories: &'a [ManifestRepository],
+    url: &str,
+) -> Result<&'a ManifestRepository, TeamError> {
+    repositories
+        .iter()
+        .find(|r| r.url.eq_ignore_ascii_case(url))
+        .ok_or_else(|| {
+            TeamError::Invalid(format!(
+                "repository '{}' is not listed under repositories",
+                url
+            ))
+        })
+}
+
+/// Full path of a skill inside its repository, and the name it is installed as
+fn skill_source(repository: &ManifestRepository, skill: &ManifestSkill) -> (String, String) {
+    let path = match (&repository.base_path, skill.path.is_empty()) {
+        (Some(base_path), false) => format!("{}/{}", base_path, skill.path),
+        (Some(base_path), true) => base_path.clone(),
+        (None, _) => skill.path.clone(),
+    };
+
+    let name = match path.rsplit('/').next().filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => split_repository(&repository.url)
+            .map(|(_, repo)| repo.to_string())
+            .unwrap_or_default(),
+    };
+
+    (path, name)
+}
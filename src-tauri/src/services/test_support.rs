@@ -0,0 +1,15 @@
+/// Skill files, keyed by relative path, from `(path, content)` pairs
+pub fn files<T: FromIterator<(String, Vec<u8>)>>(entries: &[(&str, &str)]) -> T {
+    entries
+        .iter()
+        .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+        .collect()
+}
+
+/// Owned copies of `(&str, &str)` pairs, for comparing with results
+pub fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect()
+}
@@ -0,0 +1,212 @@
+use crate::models::{SkillManifest, TrashEntry};
+use crate::services::skill::{
+    move_dir, read_dir_files, write_skill_files, SkillError, SkillService, MANIFEST_FILE_NAME,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Metadata file stored in each trash entry
+const TRASH_FILE_NAME: &str = "trash.json";
+
+/// The uninstalled skill directory inside a trash entry
+const TRASH_SKILL_DIR: &str = "skill";
+
+/// The pristine upstream copy inside a trash entry
+const TRASH_PRISTINE_DIR: &str = "pristine";
+
+pub struct TrashService;
+
+impl TrashService {
+    /// Get the trash directory path
+    fn get_trash_dir() -> Result<PathBuf, SkillError> {
+        let home = dirs::home_dir().ok_or(SkillError::HomeNotFound)?;
+        let trash_dir = home.join(".myskills").join("trash");
+
+        if !trash_dir.exists() {
+            fs::create_dir_all(&trash_dir)?;
+        }
+
+        Ok(trash_dir)
+    }
+
+    /// Get the directory of a trash entry, rejecting ids that escape the trash
+    fn get_entry_path(trash_id: &str) -> Result<PathBuf, SkillError> {
+        if trash_id.is_empty() || trash_id.starts_with('.') || trash_id.contains(['/', '\\']) {
+            return Err(SkillError::NotFound(trash_id.to_string()));
+        }
+
+        let entry_path = Self::get_trash_dir()?.join(trash_id);
+        if !entry_path.join(TRASH_FILE_NAME).exists() {
+            return Err(SkillError::NotFound(trash_id.to_string()));
+        }
+
+        Ok(entry_path)
+    }
+
+    /// Move an installed skill (and its pristine copy) into the trash
+    pub fn move_to_trash(skill_name: &str) -> Result<TrashEntry, SkillError> {
+        let skill_path = SkillService::require_skill_path(skill_name)?;
+
+        let origin = fs::read_to_string(skill_path.join(MANIFEST_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str::<SkillManifest>(&content).ok())
+            .and_then(|manifest| manifest.origin);
+
+        let now = chrono::Utc::now();
+        let trash_dir = Self::get_trash_dir()?;
+        let mut id = format!("{}-{}", skill_name, now.format("%Y%m%dT%H%M%S%3fZ"));
+        let mut suffix = 1;
+        while trash_dir.join(&id).exists() {
+            id = format!(
+                "{}-{}-{}",
+                skill_name,
+                now.format("%Y%m%dT%H%M%S%3fZ"),
+                suffix
+            );
+            suffix += 1;
+        }
+
+        let entry = TrashEntry {
+            id,
+            skill_name: skill_name.to_string(),
+            deleted_at: now.to_rfc3339(),
+            origin,
+        };
+
+        // The skill is moved first and the entry is only listed once its
+        // metadata is written last, so a failure never loses the skill
+        let metadata = serde_json::to_string_pretty(&entry)?;
+        let entry_path = trash_dir.join(&entry.id);
+        let trashed_skill_path = entry_path.join(TRASH_SKILL_DIR);
+        fs::create_dir_all(&entry_path)?;
+        if let Err(e) = move_dir(&skill_path, &trashed_skill_path) {
+            let _ = fs::remove_dir_all(&entry_path);
+            return Err(e);
+        }
+
+        // The pristine copy only helps later merges, so losing it does not
+        // stop the uninstall
+        let pristine_stored = match Self::store_pristine(skill_name, &entry_path) {
+            Ok(stored) => stored,
+            Err(e) => {
+                eprintln!(
+                    "[Rust] Failed to keep the pristine copy of '{}' in the trash: {}",
+                    skill_name, e
+                );
+                false
+            }
+        };
+
+        if let Err(e) = fs::write(entry_path.join(TRASH_FILE_NAME), metadata) {
+            if move_dir(&trashed_skill_path, &skill_path).is_ok() {
+                let _ = fs::remove_dir_all(&entry_path);
+            }
+            return Err(e.into());
+        }
+
+        if pristine_stored {
+            if let Err(e) = SkillService::remove_pristine(skill_name) {
+                eprintln!(
+                    "[Rust] Failed to remove the pristine copy of '{}': {}",
+                    skill_name, e
+                );
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Copy the pristine copy of a skill into its trash entry. Returns
+    /// whether the skill had one.
+    fn store_pristine(skill_name: &str, entry_path: &Path) -> Result<bool, SkillError> {
+        let Some(pristine) = SkillService::load_pristine(skill_name)? else {
+            return Ok(false);
+        };
+        let pristine: Vec<(String, Vec<u8>)> = pristine.into_iter().collect();
+        write_skill_files(&entry_path.join(TRASH_PRISTINE_DIR), &pristine)?;
+        Ok(true)
+    }
+
+    /// List skills in the trash, most recently deleted first
+    pub fn list() -> Result<Vec<TrashEntry>, SkillError> {
+        let trash_dir = Self::get_trash_dir()?;
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(&trash_dir)? {
+            let path = entry?.path();
+            let Ok(content) = fs::read_to_string(path.join(TRASH_FILE_NAME)) else {
+                continue;
+            };
+            if let Ok(entry) = serde_json::from_str::<TrashEntry>(&content) {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(entries)
+    }
+
+    /// Move a skill from the trash back into the skills directory
+    pub fn restore(trash_id: &str) -> Result<String, SkillError> {
+        let entry_path = Self::get_entry_path(trash_id)?;
+        let entry: TrashEntry =
+            serde_json::from_str(&fs::read_to_string(entry_path.join(TRASH_FILE_NAME))?)?;
+
+        if SkillService::is_installed(&entry.skill_name)? {
+            return Err(SkillError::AlreadyInstalled(entry.skill_name));
+        }
+
+        let skill_path = SkillService::get_skills_dir()?.join(&entry.skill_name);
+
+        move_dir(&entry_path.join(TRASH_SKILL_DIR), &skill_path)?;
+
+        let pristine_path = entry_path.join(TRASH_PRISTINE_DIR);
+        if pristine_path.exists() {
+            let pristine: Vec<(String, Vec<u8>)> =
+                read_dir_files(&pristine_path)?.into_iter().collect();
+            SkillService::save_pristine(&entry.skill_name, &pristine)?;
+        }
+
+        fs::remove_dir_all(&entry_path)?;
+        Ok(skill_path.to_string_lossy().to_string())
+    }
+
+    /// Permanently delete everything in the trash
+    pub fn empty() -> Result<usize, SkillError> {
+        let trash_dir = Self::get_trash_dir()?;
+        let mut removed = 0;
+
+        for entry in fs::read_dir(&trash_dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Permanently delete entries older than `retention_days` (0 keeps them forever)
+    pub fn purge_expired(retention_days: u32) -> Result<usize, SkillError> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+
+        let trash_dir = Self::get_trash_dir()?;
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days.into());
+        let mut removed = 0;
+
+        for entry in Self::list()? {
+            let expired = chrono::DateTime::parse_from_rfc3339(&entry.deleted_at)
+                .map(|deleted_at| deleted_at < cutoff)
+                .unwrap_or(false);
+            if expired {
+                fs::remove_dir_all(trash_dir.join(&entry.id))?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+}
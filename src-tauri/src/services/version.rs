@@ -0,0 +1,277 @@
+use crate::models::{SkillManifest, SkillVersion, VersionReason};
+use crate::services::merge::SkillFiles;
+use crate::services::skill::{
+    read_dir_files, write_skill_files, SkillError, SkillService, MANIFEST_FILE_NAME,
+};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Metadata file stored in each version snapshot
+const VERSION_FILE_NAME: &str = "version.json";
+
+/// Copy of the skill directory inside a snapshot
+const SNAPSHOT_SKILL_DIR: &str = "skill";
+
+/// Copy of the pristine upstream version inside a snapshot
+const SNAPSHOT_PRISTINE_DIR: &str = "pristine";
+
+pub struct VersionService;
+
+impl VersionService {
+    /// Get the versions directory for a skill
+    fn get_skill_versions_dir(skill_name: &str) -> Result<PathBuf, SkillError> {
+        let home = dirs::home_dir().ok_or(SkillError::HomeNotFound)?;
+        Ok(home.join(".myskills").join("versions").join(skill_name))
+    }
+
+    /// List saved versions of a skill, newest first
+    pub fn list_versions(skill_name: &str) -> Result<Vec<SkillVersion>, SkillError> {
+        let versions_dir = Self::get_skill_versions_dir(skill_name)?;
+        let mut versions = Vec::new();
+
+        if !versions_dir.exists() {
+            return Ok(versions);
+        }
+
+        for entry in fs::read_dir(&versions_dir)? {
+            let path = entry?.path();
+
+            // Skip snapshots that were never completed
+            if path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(path.join(VERSION_FILE_NAME)) else {
+                continue;
+            };
+            if let Ok(version) = serde_json::from_str::<SkillVersion>(&content) {
+                versions.push(version);
+            }
+        }
+
+        // Version ids are timestamps, so they sort chronologically
+        versions.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(versions)
+    }
+
+    /// Snapshot the currently installed state of a skill, keeping at most
+    /// `keep` versions. Nothing is stored when the skill is not installed or
+    /// is identical to the newest snapshot.
+    pub fn snapshot(
+        skill_name: &str,
+        reason: VersionReason,
+        keep: usize,
+    ) -> Result<Option<SkillVersion>, SkillError> {
+        if keep == 0 {
+            return Ok(None);
+        }
+
+        let version = Self::save_snapshot(skill_name, reason)?;
+        Self::prune(skill_name, keep)?;
+        Ok(version)
+    }
+
+    /// Snapshot the installed state and restore `version_id` in its place.
+    /// Pruning waits until the restore is done, so a full history can still
+    /// be rolled back to its oldest version.
+    pub fn rollback(skill_name: &str, version_id: &str, keep: usize) -> Result<String, SkillError> {
+        if keep > 0 {
+            Self::save_snapshot(skill_name, VersionReason::Rollback)?;
+        }
+
+        let path = Self::restore(skill_name, version_id)?;
+        Self::prune(skill_name, keep)?;
+        Ok(path)
+    }
+
+    /// Store a snapshot of the installed skill without pruning older ones
+    fn save_snapshot(
+        skill_name: &str,
+        reason: VersionReason,
+    ) -> Result<Option<SkillVersion>, SkillError> {
+        let Some(skill_path) = SkillService::find_skill_path(skill_name)? else {
+            return Ok(None);
+        };
+
+        let files = read_dir_files(&skill_path)?;
+        let digest = digest_files(&files);
+
+        if let Some(latest) = Self::list_versions(skill_name)?.first() {
+            if latest.digest == digest {
+                return Ok(None);
+            }
+        }
+
+        let origin = files
+            .get(MANIFEST_FILE_NAME)
+            .and_then(|content| serde_json::from_slice::<SkillManifest>(content).ok())
+            .and_then(|manifest| manifest.origin);
+
+        let versions_dir = Self::get_skill_versions_dir(skill_name)?;
+        let now = chrono::Utc::now();
+        let mut id = now.format("%Y%m%dT%H%M%S%3fZ").to_string();
+        let mut suffix = 1;
+        while versions_dir.join(&id).exists() {
+            id = format!("{}-{}", now.format("%Y%m%dT%H%M%S%3fZ"), suffix);
+            suffix += 1;
+        }
+
+        let version = SkillVersion {
+            id,
+            skill_name: skill_name.to_string(),
+            created_at: now.to_rfc3339(),
+            reason,
+            origin,
+            file_count: files.len() as u32,
+            digest,
+        };
+
+        // Build the snapshot under a temporary name so a partial one is never listed
+        let temp_path = versions_dir.join(format!(".{}.tmp", version.id));
+        let pristine = SkillService::load_pristine(skill_name)?;
+        let result = write_snapshot(&temp_path, files, pristine, &version).and_then(|_| {
+            fs::rename(&temp_path, versions_dir.join(&version.id)).map_err(SkillError::from)
+        });
+
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&temp_path);
+            return Err(e);
+        }
+
+        Ok(Some(version))
+    }
+
+    /// Remove all but the newest `keep` versions of a skill
+    fn prune(skill_name: &str, keep: usize) -> Result<(), SkillError> {
+        let versions_dir = Self::get_skill_versions_dir(skill_name)?;
+
+        for version in Self::list_versions(skill_name)?.into_iter().skip(keep) {
+            fs::remove_dir_all(versions_dir.join(&version.id))?;
+        }
+
+        Ok(())
+    }
+
+    /// Restore a saved version of a skill in place of the installed one
+    ///
+    /// The snapshot is staged completely and swapped in with a rename, and
+    /// its pristine copy is restored so later updates merge against it.
+    pub fn restore(skill_name: &str, version_id: &str) -> Result<String, SkillError> {
+        let snapshot_path = Self::get_skill_versions_dir(skill_name)?.join(version_id);
+        let snapshot_skill_path = snapshot_path.join(SNAPSHOT_SKILL_DIR);
+
+        if version_id.starts_with('.')
+            || version_id.contains(['/', '\\'])
+            || !snapshot_skill_path.exists()
+        {
+            return Err(SkillError::NotFound(format!(
+                "{}@{}",
+                skill_name, version_id
+            )));
+        }
+
+        let files: Vec<(String, Vec<u8>)> =
+            read_dir_files(&snapshot_skill_path)?.into_iter().collect();
+
+        let staging_path = SkillService::create_staging_path(skill_name)?;
+        if let Err(e) = write_skill_files(&staging_path, &files) {
+            let _ = fs::remove_dir_all(&staging_path);
+            return Err(e);
+        }
+
+        // Restore into wherever the skill currently lives, so a disabled skill stays disabled
+        let skill_path = match SkillService::find_skill_path(skill_name)? {
+            Some(path) => path,
+            None => SkillService::get_skills_dir()?.join(skill_name),
+        };
+        SkillService::move_into_place(&staging_path, &skill_path)?;
+
+        let snapshot_pristine_path = snapshot_path.join(SNAPSHOT_PRISTINE_DIR);
+        if snapshot_pristine_path.exists() {
+            let pristine: Vec<(String, Vec<u8>)> = read_dir_files(&snapshot_pristine_path)?
+                .into_iter()
+                .collect();
+            SkillService::save_pristine(skill_name, &pristine)?;
+        } else {
+            SkillService::remove_pristine(skill_name)?;
+        }
+
+        Ok(skill_path.to_string_lossy().to_string())
+    }
+}
+
+/// Write the files, pristine copy and metadata of a snapshot into `dir`
+fn write_snapshot(
+    dir: &Path,
+    files: SkillFiles,
+    pristine: Option<SkillFiles>,
+    version: &SkillVersion,
+) -> Result<(), SkillError> {
+    fs::create_dir_all(dir)?;
+
+    let files: Vec<(String, Vec<u8>)> = files.into_iter().collect();
+    write_skill_files(&dir.join(SNAPSHOT_SKILL_DIR), &files)?;
+
+    if let Some(pristine) = pristine {
+        let pristine: Vec<(String, Vec<u8>)> = pristine.into_iter().collect();
+        write_skill_files(&dir.join(SNAPSHOT_PRISTINE_DIR), &pristine)?;
+    }
+
+    fs::write(
+        dir.join(VERSION_FILE_NAME),
+        serde_json::to_string_pretty(version)?,
+    )?;
+    Ok(())
+}
+
+/// Hash a whole set of files so identical snapshots can be detected
+fn digest_files(files: &SkillFiles) -> String {
+    let mut hasher = Sha256::new();
+    for (path, content) in files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(content));
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_back_to_the_oldest_version_of_a_full_history() {
+        let home =
+            std::env::temp_dir().join(format!("myskills-version-test-{}", std::process::id()));
+        std::env::set_var("HOME", &home);
+        let skill_path = SkillService::get_skills_dir().unwrap().join("demo");
+        let keep = 3;
+
+        for i in 0..keep {
+            write_skill_files(
+                &skill_path,
+                &[("SKILL.md".to_string(), format!("v{}", i).into_bytes())],
+            )
+            .unwrap();
+            VersionService::snapshot("demo", VersionReason::Update, keep).unwrap();
+        }
+        let versions = VersionService::list_versions("demo").unwrap();
+        assert_eq!(versions.len(), keep);
+
+        fs::write(skill_path.join("SKILL.md"), "edited").unwrap();
+        let oldest = &versions[keep - 1];
+        let result = VersionService::rollback("demo", &oldest.id, keep);
+        let content = fs::read_to_string(skill_path.join("SKILL.md"));
+        let versions = VersionService::list_versions("demo").unwrap();
+        let _ = fs::remove_dir_all(&home);
+
+        result.unwrap();
+        assert_eq!(content.unwrap(), "v0");
+        assert_eq!(versions.len(), keep);
+        assert_eq!(versions[0].reason, VersionReason::Rollback);
+    }
+}
@@ -4,9 +4,11 @@ import { StorePage } from "@/pages/StorePage";
 import { InstalledPage } from "@/pages/InstalledPage";
 import { CreatePage } from "@/pages/CreatePage";
 import { SettingsPage } from "@/pages/SettingsPage";
+import { useCatalogChanges } from "@/hooks/useSkills";
 
 function App() {
   const [currentPage, setCurrentPage] = useState<PageType>("store");
+  useCatalogChanges();
 
   const renderPage = () => {
     switch (currentPage) {
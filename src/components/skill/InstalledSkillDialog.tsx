@@ -9,6 +9,7 @@ import {
   FileText,
   Copy,
   Check,
+  GitBranch,
 } from "lucide-react";
 import {
   Dialog,
@@ -160,6 +161,23 @@ export function InstalledSkillDialog({
           </Button>
         </div>
 
+        {/* Source Info */}
+        {skill.origin && (
+          <div className="flex items-center gap-2 py-2 px-3 bg-muted/50 rounded-lg text-sm text-muted-foreground">
+            <GitBranch className="h-4 w-4 flex-shrink-0" />
+            <span className="flex-shrink-0">{t("installed.source")}</span>
+            <code className="truncate flex-1">
+              {skill.origin.repository}
+              {skill.origin.path ? `/${skill.origin.path}` : ""}
+              {skill.origin.commit_sha
+                ? `@${skill.origin.commit_sha.slice(0, 7)}`
+                : skill.origin.git_ref
+                  ? `@${skill.origin.git_ref}`
+                  : ""}
+            </code>
+          </div>
+        )}
+
         {/* Skill Content */}
         <div className="flex-1 overflow-auto py-4">
           {loading ? (
@@ -1,3 +1,4 @@
+import { useEffect, useState } from "react";
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import {
   skillsApi,
@@ -6,6 +7,11 @@ import {
   type SyncResult,
   type Repository,
   type CreateSkillFile,
+  type GitHubAuthStatus,
+  type TokenValidation,
+  type RateLimitInfo,
+  type ProgressEvent,
+  type OfflineStatus,
 } from "@/lib/api/skills";
 
 // Query keys
@@ -21,6 +27,15 @@ export const repoKeys = {
   list: () => [...repoKeys.all, "list"] as const,
 };
 
+export const authKeys = {
+  status: ["githubAuth", "status"] as const,
+  rateLimit: ["githubAuth", "rateLimit"] as const,
+};
+
+export const offlineKeys = {
+  status: ["offline", "status"] as const,
+};
+
 /**
  * Hook to fetch store skills
  */
@@ -42,13 +57,17 @@ export function useStoreSkills() {
 export function useSyncRepositories() {
   const queryClient = useQueryClient();
 
-  return useMutation<SyncResult>({
-    mutationFn: () => skillsApi.syncRepositories(),
+  return useMutation<SyncResult, Error, string | void>({
+    mutationFn: (operationId) => skillsApi.syncRepositories(operationId || undefined),
     onSuccess: async () => {
       // Refetch cached skills after successful sync
       // Use refetch instead of invalidate to force immediate update
       await queryClient.refetchQueries({ queryKey: skillKeys.store() });
     },
+    // A sync may have switched to offline mode after connection failures
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: offlineKeys.status });
+    },
   });
 }
 
@@ -58,11 +77,89 @@ export function useSyncRepositories() {
 export function useForceSyncRepositories() {
   const queryClient = useQueryClient();
 
-  return useMutation<SyncResult>({
-    mutationFn: () => skillsApi.forceSyncRepositories(),
+  return useMutation<SyncResult, Error, string | void>({
+    mutationFn: (operationId) => skillsApi.forceSyncRepositories(operationId || undefined),
+    onSuccess: async () => {
+      await queryClient.refetchQueries({ queryKey: skillKeys.store() });
+    },
+    // A sync may have switched to offline mode after connection failures
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: offlineKeys.status });
+    },
+  });
+}
+
+/**
+ * Hook to sync a single repository
+ */
+export function useSyncRepository() {
+  const queryClient = useQueryClient();
+
+  return useMutation<
+    SyncResult,
+    Error,
+    { repoId: string; force?: boolean; operationId?: string }
+  >({
+    mutationFn: ({ repoId, force, operationId }) =>
+      skillsApi.syncRepository(repoId, force, operationId),
     onSuccess: async () => {
+      queryClient.invalidateQueries({ queryKey: repoKeys.list() });
       await queryClient.refetchQueries({ queryKey: skillKeys.store() });
     },
+    // A sync may have switched to offline mode after connection failures
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: offlineKeys.status });
+    },
+  });
+}
+
+/**
+ * Hook to reload the store when a background refresh changed the catalog
+ */
+export function useCatalogChanges() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = skillsApi.onCatalogChanged((event) => {
+      console.log("[useCatalogChanges] Catalog changed:", event.repositories);
+      queryClient.invalidateQueries({ queryKey: repoKeys.list() });
+      queryClient.refetchQueries({ queryKey: skillKeys.store() });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+}
+
+/**
+ * Hook to follow the progress events of one operation
+ */
+export function useOperationProgress(operationId: string | null) {
+  const [progress, setProgress] = useState<ProgressEvent | null>(null);
+
+  useEffect(() => {
+    setProgress(null);
+    if (!operationId) return;
+
+    const unlisten = skillsApi.onOperationProgress((event) => {
+      if (event.operation_id === operationId) {
+        setProgress(event);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [operationId]);
+
+  return progress;
+}
+
+/**
+ * Hook to cancel a running sync or install
+ */
+export function useCancelOperation() {
+  return useMutation<boolean, Error, string>({
+    mutationFn: (operationId) => skillsApi.cancelOperation(operationId),
   });
 }
 
@@ -84,10 +181,10 @@ export function useInstalledSkills() {
 export function useInstallSkill() {
   const queryClient = useQueryClient();
 
-  return useMutation<string, Error, string>({
-    mutationFn: async (skillId: string) => {
+  return useMutation<string, Error, { skillId: string; operationId?: string }>({
+    mutationFn: async ({ skillId, operationId }) => {
       console.log("[useInstallSkill] Installing skill:", skillId);
-      const result = await skillsApi.installSkill(skillId);
+      const result = await skillsApi.installSkill(skillId, operationId);
       console.log("[useInstallSkill] Install result:", result);
       return result;
     },
@@ -178,8 +275,16 @@ export function useAddRepository() {
   >({
     mutationFn: ({ owner, repo, basePath, gitRef }) =>
       skillsApi.addRepository(owner, repo, basePath, gitRef),
-    onSuccess: () => {
+    onSuccess: (repository) => {
       queryClient.invalidateQueries({ queryKey: repoKeys.list() });
+      // Only the new repository needs to be scanned
+      skillsApi
+        .syncRepository(repository.id)
+        .then(() => {
+          queryClient.invalidateQueries({ queryKey: repoKeys.list() });
+          return queryClient.refetchQueries({ queryKey: skillKeys.store() });
+        })
+        .catch((error) => console.error("[useAddRepository] Sync failed:", error));
     },
   });
 }
@@ -198,6 +303,94 @@ export function useRemoveRepository() {
   });
 }
 
+// ===== GitHub Token Hooks =====
+
+/**
+ * Hook to get which GitHub tokens are configured
+ */
+export function useGitHubAuthStatus() {
+  return useQuery<GitHubAuthStatus>({
+    queryKey: authKeys.status,
+    queryFn: () => skillsApi.getGitHubAuthStatus(),
+  });
+}
+
+/**
+ * Hook to store a GitHub token
+ */
+export function useSetGitHubToken() {
+  const queryClient = useQueryClient();
+
+  return useMutation<void, Error, { token: string; repository?: string }>({
+    mutationFn: ({ token, repository }) => skillsApi.setGitHubToken(token, repository),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: authKeys.status });
+      queryClient.invalidateQueries({ queryKey: authKeys.rateLimit });
+    },
+  });
+}
+
+/**
+ * Hook to remove a GitHub token
+ */
+export function useRemoveGitHubToken() {
+  const queryClient = useQueryClient();
+
+  return useMutation<boolean, Error, string | undefined>({
+    mutationFn: (repository) => skillsApi.removeGitHubToken(repository),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: authKeys.status });
+      queryClient.invalidateQueries({ queryKey: authKeys.rateLimit });
+    },
+  });
+}
+
+/**
+ * Hook to validate a GitHub token
+ */
+export function useValidateGitHubToken() {
+  return useMutation<TokenValidation, Error, { token?: string; repository?: string }>({
+    mutationFn: ({ token, repository }) => skillsApi.validateGitHubToken(token, repository),
+  });
+}
+
+/**
+ * Hook to get the remaining GitHub API quota
+ */
+export function useRateLimit() {
+  return useQuery<RateLimitInfo>({
+    queryKey: authKeys.rateLimit,
+    queryFn: () => skillsApi.getRateLimit(),
+    refetchInterval: 60 * 1000,
+  });
+}
+
+/**
+ * Hook to follow offline mode, which is also entered after connection failures
+ */
+export function useOfflineStatus() {
+  return useQuery<OfflineStatus>({
+    queryKey: offlineKeys.status,
+    queryFn: () => skillsApi.getOfflineStatus(),
+    refetchInterval: 30 * 1000,
+  });
+}
+
+/**
+ * Hook to turn offline mode on or off
+ */
+export function useSetOfflineMode() {
+  const queryClient = useQueryClient();
+
+  return useMutation<OfflineStatus, Error, boolean>({
+    mutationFn: (offline) => skillsApi.setOfflineMode(offline),
+    onSuccess: (status) => {
+      queryClient.setQueryData(offlineKeys.status, status);
+      queryClient.invalidateQueries({ queryKey: authKeys.rateLimit });
+    },
+  });
+}
+
 /**
  * Hook to create a custom skill
  */
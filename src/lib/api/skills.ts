@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 // Types matching Rust models
 export interface Skill {
@@ -41,12 +42,220 @@ export interface InstalledSkill {
   description: string;
   path: string;
   installed_at: string;
+  enabled: boolean;
+  origin?: SkillOrigin;
+}
+
+export interface SkillOrigin {
+  repository: string;
+  path: string;
+  git_ref?: string;
+  commit_sha?: string;
+  tree_sha?: string;
+}
+
+export interface SkillUpdateInfo {
+  skill_name: string;
+  repository: string;
+  path: string;
+  installed_sha?: string;
+  latest_sha?: string;
+  has_update: boolean;
+  error?: string;
+}
+
+export type ConflictResolution = "merge" | "mine" | "theirs";
+
+export type FileMergeStatus =
+  | "updated"
+  | "local"
+  | "merged"
+  | "removed"
+  | "conflict";
+
+export interface FileMergeResult {
+  path: string;
+  status: FileMergeStatus;
+  resolution?: ConflictResolution;
+  conflict_markers?: number;
+}
+
+export interface SkillUpdateResult {
+  skill_name: string;
+  path?: string;
+  files: FileMergeResult[];
+  has_conflicts: boolean;
+}
+
+export type VersionReason = "install" | "update" | "uninstall" | "rollback";
+
+export interface SkillVersion {
+  id: string;
+  skill_name: string;
+  created_at: string;
+  reason: VersionReason;
+  origin?: SkillOrigin;
+  file_count: number;
+  digest: string;
+}
+
+export interface TrashEntry {
+  id: string;
+  skill_name: string;
+  deleted_at: string;
+  origin?: SkillOrigin;
+}
+
+export interface SkillProfile {
+  id: string;
+  name: string;
+  skills: string[];
+}
+
+export interface ProfileActivation {
+  profile_id: string;
+  enabled: string[];
+  disabled: string[];
+  missing: string[];
+}
+
+export type PlanAction = "install" | "update" | "remove" | "unchanged" | "conflict";
+
+export interface PlanItem {
+  skill_name: string;
+  action: PlanAction;
+  repository?: string;
+  path?: string;
+  git_ref?: string;
+  installed_commit?: string;
+  target_commit?: string;
+  content_hash?: string;
+  reason?: string;
+}
+
+export interface SkillsPlan {
+  manifest_path: string;
+  items: PlanItem[];
+}
+
+export interface PlanItemResult {
+  item: PlanItem;
+  success: boolean;
+  error?: string;
+}
+
+export interface SkillsApplyResult {
+  manifest_path: string;
+  success: boolean;
+  results: PlanItemResult[];
+}
+
+export interface LockedSkill {
+  name: string;
+  repository: string;
+  path: string;
+  git_ref?: string;
+  commit: string;
+  content_hash: string;
+}
+
+export interface Lockfile {
+  version: number;
+  generated_at: string;
+  skills: LockedSkill[];
+}
+
+export interface RateLimitInfo {
+  limit: number;
+  remaining: number;
+  used: number;
+  reset_at: string;
+}
+
+export interface TokenValidation {
+  valid: boolean;
+  login?: string;
+  scopes: string[];
+  rate_limit?: RateLimitInfo;
+  error?: string;
+}
+
+export type TokenSource = "keychain" | "env" | "gh" | "git";
+
+export interface GitHubAuthStatus {
+  has_token: boolean;
+  source: TokenSource | null;
+  repository_overrides: string[];
+}
+
+export type OperationKind = "sync" | "install";
+
+export type ProgressStage = "started" | "progress" | "finished" | "failed" | "cancelled";
+
+/**
+ * Progress of a sync or install, emitted as the "operation-progress" event.
+ * `done`/`total` count repositories for syncs and files for installs.
+ */
+export interface ProgressEvent {
+  operation_id: string;
+  kind: OperationKind;
+  stage: ProgressStage;
+  repository?: string;
+  skill?: string;
+  file?: string;
+  bytes?: number;
+  total_bytes?: number;
+  done?: number;
+  total?: number;
+  message?: string;
+  /** When a sync paused by the rate limit resumes (ISO 8601) */
+  resume_at?: string;
+}
+
+/** Error message of a cancelled operation */
+export const OPERATION_CANCELLED = "Operation cancelled";
+
+export type RepositorySyncStatus = "builtin" | "cached" | "scanned" | "failed";
+
+export type SyncErrorKind =
+  | "invalid_repository"
+  | "not_found"
+  | "rate_limited"
+  | "forbidden"
+  | "network"
+  | "offline"
+  | "other";
+
+export interface RepositorySyncReport {
+  repository_id: string;
+  repository: string;
+  status: RepositorySyncStatus;
+  skill_count: number;
+  duration_ms: number;
+  error_kind?: SyncErrorKind;
+  error?: string;
+  cache_age_secs?: number;
+  commit_sha?: string;
+}
+
+/** Why GitHub is not contacted */
+export type OfflineReason = "user" | "connection_failures";
+
+export interface OfflineStatus {
+  offline: boolean;
+  reason?: OfflineReason;
+}
+
+/** Repositories whose skills changed in a background refresh */
+export interface CatalogChangedEvent {
+  repositories: RepositorySyncReport[];
 }
 
 export interface SyncResult {
   success: boolean;
   skills_found: number;
   message: string;
+  repositories: RepositorySyncReport[];
 }
 
 export interface Repository {
@@ -72,15 +281,51 @@ export const skillsApi = {
   /**
    * Sync skills from all configured repositories
    */
-  syncRepositories: async (): Promise<SyncResult> => {
-    return invoke<SyncResult>("sync_repositories");
+  syncRepositories: async (operationId?: string): Promise<SyncResult> => {
+    return invoke<SyncResult>("sync_repositories", { operationId: operationId ?? null });
   },
 
   /**
    * Force sync - clears cache and re-fetches from GitHub
    */
-  forceSyncRepositories: async (): Promise<SyncResult> => {
-    return invoke<SyncResult>("force_sync_repositories");
+  forceSyncRepositories: async (operationId?: string): Promise<SyncResult> => {
+    return invoke<SyncResult>("force_sync_repositories", { operationId: operationId ?? null });
+  },
+
+  /**
+   * Sync a single repository and merge its skills into the store
+   */
+  syncRepository: async (
+    repoId: string,
+    force?: boolean,
+    operationId?: string
+  ): Promise<SyncResult> => {
+    return invoke<SyncResult>("sync_repository", {
+      repoId,
+      force: force ?? null,
+      operationId: operationId ?? null,
+    });
+  },
+
+  /**
+   * Cancel a running sync or install
+   */
+  cancelOperation: async (operationId: string): Promise<boolean> => {
+    return invoke<boolean>("cancel_operation", { operationId });
+  },
+
+  /**
+   * Subscribe to progress events of syncs and installs
+   */
+  onOperationProgress: (handler: (event: ProgressEvent) => void): Promise<UnlistenFn> => {
+    return listen<ProgressEvent>("operation-progress", (event) => handler(event.payload));
+  },
+
+  /**
+   * Subscribe to background refreshes that changed the store catalog
+   */
+  onCatalogChanged: (handler: (event: CatalogChangedEvent) => void): Promise<UnlistenFn> => {
+    return listen<CatalogChangedEvent>("catalog-changed", (event) => handler(event.payload));
   },
 
   /**
@@ -90,6 +335,20 @@ export const skillsApi = {
     return invoke<Skill[]>("get_cached_skills");
   },
 
+  /**
+   * Get a skill of the store catalog by id
+   */
+  getSkill: async (skillId: string): Promise<Skill | null> => {
+    return invoke<Skill | null>("get_skill", { skillId });
+  },
+
+  /**
+   * Get the store skills of one repository
+   */
+  getRepositorySkills: async (repoId: string): Promise<Skill[]> => {
+    return invoke<Skill[]>("get_repository_skills", { repoId });
+  },
+
   /**
    * List installed skills
    */
@@ -107,8 +366,8 @@ export const skillsApi = {
   /**
    * Install a skill
    */
-  installSkill: async (skillId: string): Promise<string> => {
-    return invoke<string>("install_skill", { skillId });
+  installSkill: async (skillId: string, operationId?: string): Promise<string> => {
+    return invoke<string>("install_skill", { skillId, operationId: operationId ?? null });
   },
 
   /**
@@ -119,6 +378,244 @@ export const skillsApi = {
     return invoke<void>("uninstall_skill", { skillName });
   },
 
+  /**
+   * Check installed skills for upstream updates
+   */
+  checkSkillUpdates: async (): Promise<SkillUpdateInfo[]> => {
+    return invoke<SkillUpdateInfo[]>("check_skill_updates");
+  },
+
+  /**
+   * Preview which files an update would change, merge or conflict
+   */
+  previewSkillUpdate: async (skillName: string): Promise<SkillUpdateResult> => {
+    return invoke<SkillUpdateResult>("preview_skill_update", { skillName });
+  },
+
+  /**
+   * Update an installed skill, merging upstream changes with local edits
+   */
+  updateSkill: async (
+    skillName: string,
+    resolutions?: Record<string, ConflictResolution>
+  ): Promise<SkillUpdateResult> => {
+    return invoke<SkillUpdateResult>("update_skill", {
+      skillName,
+      resolutions: resolutions ?? null,
+    });
+  },
+
+  /**
+   * List saved versions of a skill, newest first
+   */
+  listSkillVersions: async (skillName: string): Promise<SkillVersion[]> => {
+    return invoke<SkillVersion[]>("list_skill_versions", { skillName });
+  },
+
+  /**
+   * Restore a saved version of a skill
+   */
+  rollbackSkill: async (skillName: string, versionId: string): Promise<string> => {
+    return invoke<string>("rollback_skill", { skillName, versionId });
+  },
+
+  /**
+   * Disable a skill without uninstalling it
+   */
+  disableSkill: async (skillName: string): Promise<string> => {
+    return invoke<string>("disable_skill", { skillName });
+  },
+
+  /**
+   * Re-enable a disabled skill
+   */
+  enableSkill: async (skillName: string): Promise<string> => {
+    return invoke<string>("enable_skill", { skillName });
+  },
+
+  // ===== Profiles =====
+
+  /**
+   * List all skill profiles
+   */
+  listProfiles: async (): Promise<SkillProfile[]> => {
+    return invoke<SkillProfile[]>("list_profiles");
+  },
+
+  /**
+   * Create a skill profile
+   */
+  createProfile: async (name: string, skills: string[]): Promise<SkillProfile> => {
+    return invoke<SkillProfile>("create_profile", { name, skills });
+  },
+
+  /**
+   * Update a skill profile
+   */
+  updateProfile: async (
+    profileId: string,
+    name?: string,
+    skills?: string[]
+  ): Promise<SkillProfile> => {
+    return invoke<SkillProfile>("update_profile", {
+      profileId,
+      name: name ?? null,
+      skills: skills ?? null,
+    });
+  },
+
+  /**
+   * Delete a skill profile
+   */
+  deleteProfile: async (profileId: string): Promise<boolean> => {
+    return invoke<boolean>("delete_profile", { profileId });
+  },
+
+  /**
+   * Switch to a profile, enabling its skills and disabling all others
+   */
+  activateProfile: async (profileId: string): Promise<ProfileActivation> => {
+    return invoke<ProfileActivation>("activate_profile", { profileId });
+  },
+
+  // ===== Trash =====
+
+  /**
+   * List uninstalled skills in the trash
+   */
+  listTrash: async (): Promise<TrashEntry[]> => {
+    return invoke<TrashEntry[]>("list_trash");
+  },
+
+  /**
+   * Restore a skill from the trash
+   */
+  restoreFromTrash: async (trashId: string): Promise<string> => {
+    return invoke<string>("restore_from_trash", { trashId });
+  },
+
+  /**
+   * Permanently delete everything in the trash
+   */
+  emptyTrash: async (): Promise<number> => {
+    return invoke<number>("empty_trash");
+  },
+
+  // ===== Skills Manifest =====
+
+  /**
+   * Show what applying a skills manifest (skills.toml) would change.
+   * `manifestPath` may be the file or its directory; defaults to the home directory.
+   */
+  planSkillsManifest: async (manifestPath?: string): Promise<SkillsPlan> => {
+    return invoke<SkillsPlan>("plan_skills_manifest", {
+      manifestPath: manifestPath ?? null,
+    });
+  },
+
+  /**
+   * Install, update and remove skills to match a skills manifest
+   */
+  applySkillsManifest: async (
+    manifestPath?: string
+  ): Promise<SkillsApplyResult> => {
+    return invoke<SkillsApplyResult>("apply_skills_manifest", {
+      manifestPath: manifestPath ?? null,
+    });
+  },
+
+  // ===== Lockfile =====
+
+  /**
+   * Write a lockfile (skills.lock) pinning every installed skill.
+   * `lockfilePath` may be the file or its directory; defaults to the home directory.
+   */
+  generateLockfile: async (lockfilePath?: string): Promise<Lockfile> => {
+    return invoke<Lockfile>("generate_lockfile", {
+      lockfilePath: lockfilePath ?? null,
+    });
+  },
+
+  /**
+   * Show which skills would be installed or updated to match a lockfile
+   */
+  planLockfile: async (lockfilePath?: string): Promise<SkillsPlan> => {
+    return invoke<SkillsPlan>("plan_lockfile", {
+      lockfilePath: lockfilePath ?? null,
+    });
+  },
+
+  /**
+   * Install the exact commits pinned in a lockfile, verifying content hashes
+   */
+  applyLockfile: async (lockfilePath?: string): Promise<SkillsApplyResult> => {
+    return invoke<SkillsApplyResult>("apply_lockfile", {
+      lockfilePath: lockfilePath ?? null,
+    });
+  },
+
+  // ===== GitHub Token =====
+
+  /**
+   * Report which GitHub tokens are configured
+   */
+  getGitHubAuthStatus: async (): Promise<GitHubAuthStatus> => {
+    return invoke<GitHubAuthStatus>("get_github_auth_status");
+  },
+
+  /**
+   * Store a GitHub token in the OS keychain (default, or for one owner/repo)
+   */
+  setGitHubToken: async (token: string, repository?: string): Promise<void> => {
+    return invoke<void>("set_github_token", {
+      token,
+      repository: repository || null,
+    });
+  },
+
+  /**
+   * Remove the default GitHub token or a repository's own token
+   */
+  removeGitHubToken: async (repository?: string): Promise<boolean> => {
+    return invoke<boolean>("remove_github_token", {
+      repository: repository || null,
+    });
+  },
+
+  /**
+   * Validate a GitHub token (or the stored one) and report scopes and quota
+   */
+  validateGitHubToken: async (
+    token?: string,
+    repository?: string
+  ): Promise<TokenValidation> => {
+    return invoke<TokenValidation>("validate_github_token", {
+      token: token || null,
+      repository: repository || null,
+    });
+  },
+
+  /**
+   * Get the core GitHub API quota, asking GitHub again when `refresh` is set
+   */
+  getRateLimit: async (refresh?: boolean): Promise<RateLimitInfo> => {
+    return invoke<RateLimitInfo>("get_rate_limit", { refresh: refresh ?? null });
+  },
+
+  /**
+   * Get whether the app works offline, from its caches only
+   */
+  getOfflineStatus: async (): Promise<OfflineStatus> => {
+    return invoke<OfflineStatus>("get_offline_status");
+  },
+
+  /**
+   * Turn offline mode on or off
+   */
+  setOfflineMode: async (offline: boolean): Promise<OfflineStatus> => {
+    return invoke<OfflineStatus>("set_offline_mode", { offline });
+  },
+
   /**
    * Get skills directory path
    */
@@ -1,11 +1,23 @@
 import { useState } from "react";
 import { useTranslation } from "react-i18next";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
-import { Globe, Moon, Sun, FolderOpen, Trash2, Plus, X, Loader2 } from "lucide-react";
+import { Globe, Moon, Sun, FolderOpen, Trash2, Plus, X, Loader2, KeyRound } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Input } from "@/components/ui/input";
-import { useSkillsDirectory, useRepositories, useAddRepository, useRemoveRepository } from "@/hooks/useSkills";
+import {
+  useSkillsDirectory,
+  useRepositories,
+  useAddRepository,
+  useRemoveRepository,
+  useGitHubAuthStatus,
+  useSetGitHubToken,
+  useRemoveGitHubToken,
+  useValidateGitHubToken,
+  useRateLimit,
+  useOfflineStatus,
+  useSetOfflineMode,
+} from "@/hooks/useSkills";
 
 export function SettingsPage() {
   const { t, i18n } = useTranslation();
@@ -17,6 +29,17 @@ export function SettingsPage() {
   const [newRepoInput, setNewRepoInput] = useState("");
   const [repoError, setRepoError] = useState<string | null>(null);
 
+  const { data: authStatus } = useGitHubAuthStatus();
+  const { data: rateLimit } = useRateLimit();
+  const { data: offlineStatus } = useOfflineStatus();
+  const setOfflineMutation = useSetOfflineMode();
+  const setTokenMutation = useSetGitHubToken();
+  const removeTokenMutation = useRemoveGitHubToken();
+  const validateTokenMutation = useValidateGitHubToken();
+  const [tokenInput, setTokenInput] = useState("");
+  const [tokenRepoInput, setTokenRepoInput] = useState("");
+  const [tokenError, setTokenError] = useState<string | null>(null);
+
   const parseRepositoryInput = (
     input: string
   ): { owner: string; repo: string; basePath?: string; gitRef?: string } | null => {
@@ -149,6 +172,44 @@ export function SettingsPage() {
     });
   };
 
+  const handleSaveToken = () => {
+    setTokenError(null);
+    const token = tokenInput.trim();
+    if (!token) return;
+
+    setTokenMutation.mutate(
+      { token, repository: tokenRepoInput.trim() || undefined },
+      {
+        onSuccess: () => {
+          setTokenInput("");
+          setTokenRepoInput("");
+        },
+        onError: (error) => setTokenError(error.message),
+      }
+    );
+  };
+
+  const handleValidateToken = () => {
+    setTokenError(null);
+    validateTokenMutation.mutate(
+      {
+        token: tokenInput.trim() || undefined,
+        repository: tokenRepoInput.trim() || undefined,
+      },
+      { onError: (error) => setTokenError(error.message) }
+    );
+  };
+
+  const handleRemoveToken = (repository?: string) => {
+    setTokenError(null);
+    validateTokenMutation.reset();
+    removeTokenMutation.mutate(repository, {
+      onError: (error) => setTokenError(error.message),
+    });
+  };
+
+  const validation = validateTokenMutation.data;
+
   const builtinRepos = repositories?.filter((r) => r.is_builtin) || [];
   const customRepos = repositories?.filter((r) => !r.is_builtin) || [];
 
@@ -276,6 +337,145 @@ export function SettingsPage() {
             </CardContent>
           </Card>
 
+          {/* GitHub Token */}
+          <Card>
+            <CardHeader>
+              <CardTitle>{t("settings.github.title")}</CardTitle>
+              <CardDescription>{t("settings.github.description")}</CardDescription>
+            </CardHeader>
+            <CardContent className="space-y-4">
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">{t("settings.github.defaultToken")}</p>
+                  <p className="text-sm text-muted-foreground">
+                    {authStatus?.has_token && authStatus.source
+                      ? t("settings.github.configuredFrom", {
+                          source: t(`settings.github.sources.${authStatus.source}`),
+                        })
+                      : t("settings.github.notConfigured")}
+                  </p>
+                  {rateLimit && (
+                    <p className="text-sm text-muted-foreground">
+                      {t("settings.github.quota", {
+                        remaining: rateLimit.remaining,
+                        limit: rateLimit.limit,
+                        reset: new Date(rateLimit.reset_at).toLocaleTimeString(),
+                      })}
+                    </p>
+                  )}
+                </div>
+                {authStatus?.source === "keychain" && (
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    className="text-destructive"
+                    onClick={() => handleRemoveToken()}
+                    disabled={removeTokenMutation.isPending}
+                  >
+                    <Trash2 className="mr-2 h-4 w-4" />
+                    {t("settings.github.remove")}
+                  </Button>
+                )}
+              </div>
+
+              {/* Per-repository tokens */}
+              {authStatus && authStatus.repository_overrides.length > 0 && (
+                <div className="space-y-2">
+                  <h4 className="text-sm font-medium">{t("settings.github.overrides")}</h4>
+                  {authStatus.repository_overrides.map((repository) => (
+                    <div
+                      key={repository}
+                      className="flex items-center justify-between p-3 rounded-lg bg-muted"
+                    >
+                      <p className="font-medium">{repository}</p>
+                      <Button
+                        size="icon"
+                        variant="ghost"
+                        className="text-destructive hover:text-destructive"
+                        onClick={() => handleRemoveToken(repository)}
+                        disabled={removeTokenMutation.isPending}
+                      >
+                        <X className="h-4 w-4" />
+                      </Button>
+                    </div>
+                  ))}
+                </div>
+              )}
+
+              {/* Add or replace a token */}
+              <div className="space-y-2">
+                <Input
+                  type="password"
+                  placeholder={t("settings.github.tokenPlaceholder")}
+                  value={tokenInput}
+                  onChange={(e) => setTokenInput(e.target.value)}
+                />
+                <Input
+                  placeholder={t("settings.github.repositoryPlaceholder")}
+                  value={tokenRepoInput}
+                  onChange={(e) => setTokenRepoInput(e.target.value)}
+                />
+                <div className="flex gap-2">
+                  <Button
+                    size="sm"
+                    onClick={handleSaveToken}
+                    disabled={setTokenMutation.isPending || !tokenInput.trim()}
+                  >
+                    {setTokenMutation.isPending ? (
+                      <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                    ) : (
+                      <KeyRound className="mr-2 h-4 w-4" />
+                    )}
+                    {t("settings.github.save")}
+                  </Button>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={handleValidateToken}
+                    disabled={validateTokenMutation.isPending}
+                  >
+                    {validateTokenMutation.isPending && (
+                      <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                    )}
+                    {t("settings.github.validate")}
+                  </Button>
+                </div>
+              </div>
+
+              {validation && (
+                <div className="text-sm space-y-1">
+                  {validation.valid ? (
+                    <>
+                      <p>{t("settings.github.valid", { login: validation.login })}</p>
+                      <p className="text-muted-foreground">
+                        {t("settings.github.scopes")}:{" "}
+                        {validation.scopes.length > 0
+                          ? validation.scopes.join(", ")
+                          : t("settings.github.noScopes")}
+                      </p>
+                    </>
+                  ) : (
+                    <p className="text-destructive">
+                      {t("settings.github.invalid")}
+                      {validation.error && `: ${validation.error}`}
+                    </p>
+                  )}
+                  {validation.rate_limit && (
+                    <p className="text-muted-foreground">
+                      {t("settings.github.quota", {
+                        remaining: validation.rate_limit.remaining,
+                        limit: validation.rate_limit.limit,
+                        reset: new Date(validation.rate_limit.reset_at).toLocaleTimeString(),
+                      })}
+                    </p>
+                  )}
+                </div>
+              )}
+
+              {tokenError && <p className="text-sm text-destructive">{tokenError}</p>}
+            </CardContent>
+          </Card>
+
           {/* Cache Settings */}
           <Card>
             <CardHeader>
@@ -315,6 +515,27 @@ export function SettingsPage() {
                   {t("settings.cache.clear")}
                 </Button>
               </div>
+              <div className="flex items-center justify-between">
+                <div>
+                  <p className="font-medium">{t("settings.cache.offline.title")}</p>
+                  <p className="text-sm text-muted-foreground">
+                    {offlineStatus?.reason === "connection_failures"
+                      ? t("settings.cache.offline.connectionFailures")
+                      : t("settings.cache.offline.hint")}
+                  </p>
+                </div>
+                <Button
+                  variant={offlineStatus?.offline ? "default" : "outline"}
+                  size="sm"
+                  disabled={setOfflineMutation.isPending}
+                  onClick={() => setOfflineMutation.mutate(!offlineStatus?.offline)}
+                >
+                  {setOfflineMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                  {offlineStatus?.offline
+                    ? t("settings.cache.offline.goOnline")
+                    : t("settings.cache.offline.goOffline")}
+                </Button>
+              </div>
             </CardContent>
           </Card>
 
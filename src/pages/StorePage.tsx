@@ -1,6 +1,6 @@
 import { useEffect, useRef, useState, useCallback } from "react";
 import { useTranslation } from "react-i18next";
-import { Search, RefreshCw, Loader2, AlertTriangle } from "lucide-react";
+import { Search, RefreshCw, Loader2, AlertTriangle, X, WifiOff } from "lucide-react";
 import { Input } from "@/components/ui/input";
 import { Button } from "@/components/ui/button";
 import { SkillCard } from "@/components/skill/SkillCard";
@@ -8,13 +8,24 @@ import { SkillDetailDialog } from "@/components/skill/SkillDetailDialog";
 import {
   useStoreSkills,
   useSyncRepositories,
+  useSyncRepository,
   useInstallSkill,
   useInstalledSkills,
   useUninstallSkill,
+  useOperationProgress,
+  useCancelOperation,
+  useOfflineStatus,
+  useSetOfflineMode,
   skillKeys,
 } from "@/hooks/useSkills";
 import { useQueryClient } from "@tanstack/react-query";
-import type { Skill, SkillCategory } from "@/lib/api/skills";
+import {
+  OPERATION_CANCELLED,
+  type ProgressEvent,
+  type RepositorySyncReport,
+  type Skill,
+  type SkillCategory,
+} from "@/lib/api/skills";
 
 const categories: { id: SkillCategory | "all"; labelKey: string }[] = [
   { id: "all", labelKey: "store.categories.all" },
@@ -35,71 +46,116 @@ export function StorePage() {
   const [selectedSkill, setSelectedSkill] = useState<Skill | null>(null);
   const [dialogOpen, setDialogOpen] = useState(false);
   const [syncError, setSyncError] = useState<string | null>(null);
+  const [failedRepos, setFailedRepos] = useState<RepositorySyncReport[]>([]);
+  const [syncOperationId, setSyncOperationId] = useState<string | null>(null);
+  const [installOperationId, setInstallOperationId] = useState<string | null>(null);
   const didAutoSync = useRef(false);
 
-  const { data: skills = [], isLoading, error, dataUpdatedAt } = useStoreSkills();
+  const { data: skills = [], isLoading, error } = useStoreSkills();
   const { data: installedSkills = [] } = useInstalledSkills();
   const queryClient = useQueryClient();
   const syncMutation = useSyncRepositories();
+  const repoSyncMutation = useSyncRepository();
   const installMutation = useInstallSkill();
   const uninstallMutation = useUninstallSkill();
+  const cancelMutation = useCancelOperation();
+  const { data: offlineStatus } = useOfflineStatus();
+  const setOfflineMutation = useSetOfflineMode();
+  const syncProgress = useOperationProgress(syncOperationId);
+  const installProgress = useOperationProgress(installOperationId);
 
-  // Auto sync on first load only if cache is empty
-  // Use dataUpdatedAt to check if we already have data from this session
+  // Auto sync once the catalog has loaded, only if it is empty. The catalog
+  // is restored from disk at startup, so cached skills render without a sync.
   useEffect(() => {
-    // Skip if already synced in this component lifecycle
-    if (didAutoSync.current) return;
-    // Skip if we already have skills data
-    if (skills.length > 0) return;
-    // Skip if query has been updated (meaning we already have data from cache)
-    if (dataUpdatedAt > 0 && skills.length === 0) {
-      // Cache exists but is empty, trigger sync
-      didAutoSync.current = true;
-      console.log("[StorePage] Cache empty, starting sync...");
-      syncMutation.mutate(undefined, {
-        onSuccess: (result) => {
-          console.log("[StorePage] Sync success:", result);
-          setSyncError(null);
-        },
-        onError: (error) => {
-          console.error("[StorePage] Sync failed:", error);
-          setSyncError(error.message);
-        },
-      });
-      return;
-    }
-    // First mount with no data
+    if (didAutoSync.current || isLoading) return;
     didAutoSync.current = true;
-    console.log("[StorePage] First mount, starting sync...");
+    if (skills.length > 0) return;
+
+    console.log("[StorePage] Catalog empty, starting sync...");
     syncMutation.mutate(undefined, {
       onSuccess: (result) => {
         console.log("[StorePage] Sync success:", result);
         setSyncError(null);
+        setFailedRepos(result.repositories.filter((r) => r.status === "failed"));
       },
       onError: (error) => {
         console.error("[StorePage] Sync failed:", error);
         setSyncError(error.message);
       },
     });
-  }, []);  // Empty deps - only run once on mount
+  }, [isLoading, skills.length]);
 
   // Manual refresh handler
   const handleRefresh = useCallback(() => {
     setSyncError(null);
-    syncMutation.mutate(undefined, {
+    const operationId = crypto.randomUUID();
+    setSyncOperationId(operationId);
+    syncMutation.mutate(operationId, {
       onSuccess: (result) => {
         console.log("[StorePage] Manual sync success:", result);
         if (!result.success) {
           setSyncError(result.message);
         }
+        setFailedRepos(result.repositories.filter((r) => r.status === "failed"));
       },
       onError: (error) => {
         console.error("[StorePage] Manual sync failed:", error);
-        setSyncError(error.message);
+        if (error.message !== OPERATION_CANCELLED) {
+          setSyncError(error.message);
+        }
       },
+      onSettled: () => setSyncOperationId(null),
     });
   }, [syncMutation]);
 
+  // Retry a single repository that failed to sync
+  const handleRetryRepository = useCallback(
+    (repoId: string) => {
+      repoSyncMutation.mutate(
+        { repoId, force: true },
+        {
+          onSuccess: (result) => {
+            setFailedRepos((current) => [
+              ...current.filter((r) => r.repository_id !== repoId),
+              ...result.repositories.filter((r) => r.status === "failed"),
+            ]);
+          },
+          onError: (error) => {
+            console.error("[StorePage] Repository sync failed:", error);
+          },
+        }
+      );
+    },
+    [repoSyncMutation]
+  );
+
+  // Describe the latest progress event of a sync or install
+  const describeProgress = (progress: ProgressEvent | null) => {
+    if (!progress) return null;
+    if (progress.kind === "sync") {
+      if (progress.resume_at) {
+        return t("store.progress.rateLimited", {
+          repository: progress.repository,
+          time: new Date(progress.resume_at).toLocaleTimeString(),
+        });
+      }
+      return progress.total !== undefined
+        ? t("store.progress.sync", { done: progress.done ?? 0, total: progress.total })
+        : null;
+    }
+    if (progress.bytes !== undefined && progress.file === undefined) {
+      return t("store.progress.download", {
+        size: (progress.bytes / 1024 / 1024).toFixed(1),
+      });
+    }
+    return progress.done !== undefined
+      ? t("store.progress.install", { done: progress.done })
+      : null;
+  };
+
+  const activeOperationId = installOperationId ?? syncOperationId;
+  const progressText = describeProgress(installOperationId ? installProgress : syncProgress);
+
   // Filter skills
   const filteredSkills = skills.filter((skill) => {
     const matchesSearch =
@@ -124,8 +180,10 @@ export function StorePage() {
   const handleInstall = async (skill: Skill) => {
     console.log("[StorePage] Installing skill:", skill.id);
     setInstallingId(skill.id);
+    const operationId = crypto.randomUUID();
+    setInstallOperationId(operationId);
     try {
-      await installMutation.mutateAsync(skill.id);
+      await installMutation.mutateAsync({ skillId: skill.id, operationId });
       console.log("[StorePage] Install mutation completed, waiting for data refresh...");
       // Wait for data to be refreshed (onSuccess in hook does this, but we need to wait)
       await queryClient.refetchQueries({ queryKey: skillKeys.installed() });
@@ -134,6 +192,7 @@ export function StorePage() {
       console.error("[StorePage] Install failed:", err);
     } finally {
       setInstallingId(null);
+      setInstallOperationId(null);
     }
   };
 
@@ -158,7 +217,13 @@ export function StorePage() {
 
   const showLoading = isLoading || (skills.length === 0 && syncMutation.isPending);
   const showError = Boolean(error) || (skills.length === 0 && syncMutation.isError);
-  const showRateLimitWarning = syncError?.includes("Rate limited") && skills.length > 0;
+  const showRateLimitWarning =
+    (failedRepos.some((r) => r.error_kind === "rate_limited") ||
+      /rate limit/i.test(syncError ?? "")) &&
+    skills.length > 0;
+  const otherFailures = failedRepos.filter(
+    (r) => r.error_kind !== "rate_limited" && r.error_kind !== "offline"
+  );
 
   return (
     <div className="flex flex-col h-full">
@@ -169,6 +234,56 @@ export function StorePage() {
           <span>{t("errors.rateLimit") || "GitHub API 请求频率受限，请稍后再试"}</span>
         </div>
       )}
+      {/* Offline Banner */}
+      {offlineStatus?.offline && (
+        <div className="px-6 py-2 bg-muted border-b flex items-center gap-2 text-sm text-muted-foreground">
+          <WifiOff className="h-4 w-4 flex-shrink-0" />
+          <span className="flex-1">
+            {offlineStatus.reason === "connection_failures"
+              ? t("store.offline.connectionFailures")
+              : t("store.offline.user")}
+          </span>
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-6 px-2"
+            disabled={setOfflineMutation.isPending}
+            onClick={() => setOfflineMutation.mutate(false)}
+          >
+            {t("store.offline.goOnline")}
+          </Button>
+        </div>
+      )}
+      {/* Repositories that failed to sync */}
+      {otherFailures.length > 0 && skills.length > 0 && (
+        <div className="px-6 py-2 bg-destructive/10 border-b border-destructive/20 text-sm text-destructive space-y-1">
+          <div className="flex items-center gap-2 font-medium">
+            <AlertTriangle className="h-4 w-4 flex-shrink-0" />
+            <span>{t("store.syncReport.failed", { count: otherFailures.length })}</span>
+          </div>
+          {otherFailures.map((report) => (
+            <div key={report.repository_id} className="pl-6 flex items-center gap-2">
+              <span>
+                {report.repository}: {report.error}
+              </span>
+              <Button
+                variant="ghost"
+                size="sm"
+                className="h-6 px-2"
+                disabled={repoSyncMutation.isPending}
+                onClick={() => handleRetryRepository(report.repository_id)}
+              >
+                {repoSyncMutation.isPending &&
+                repoSyncMutation.variables?.repoId === report.repository_id ? (
+                  <Loader2 className="h-3 w-3 animate-spin" />
+                ) : (
+                  t("store.syncReport.retry")
+                )}
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
       {/* Header */}
       <div className="border-b bg-background/95 backdrop-blur supports-[backdrop-filter]:bg-background/60">
         <div className="px-6 py-4">
@@ -179,19 +294,36 @@ export function StorePage() {
                 {t("store.subtitle")}
               </p>
             </div>
-            <Button
-              variant="outline"
-              size="sm"
-              onClick={handleRefresh}
-              disabled={syncMutation.isPending}
-            >
-              {syncMutation.isPending ? (
-                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
-              ) : (
-                <RefreshCw className="mr-2 h-4 w-4" />
+            <div className="flex items-center gap-2">
+              {activeOperationId && (
+                <>
+                  {progressText && (
+                    <span className="text-sm text-muted-foreground">{progressText}</span>
+                  )}
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => cancelMutation.mutate(activeOperationId)}
+                  >
+                    <X className="mr-2 h-4 w-4" />
+                    {t("common.cancel")}
+                  </Button>
+                </>
               )}
-              {t("common.refresh")}
-            </Button>
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={handleRefresh}
+                disabled={syncMutation.isPending}
+              >
+                {syncMutation.isPending ? (
+                  <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                ) : (
+                  <RefreshCw className="mr-2 h-4 w-4" />
+                )}
+                {t("common.refresh")}
+              </Button>
+            </div>
           </div>
 
           {/* Search */}